@@ -0,0 +1,10 @@
+/*
+ * Source of the ELF fixtures, built with
+ *   gcc [-m32] -Os -nostdlib -static -fno-asynchronous-unwind-tables -no-pie \
+ *       -Wl,-e,start_kernel -Wl,--build-id=none -Wl,-z,norelro -Wl,-z,noseparate-code \
+ *       symbols.c -o symbols{32,64}.elf
+ *   strip --strip-debug -R .comment symbols{32,64}.elf
+ */
+int counter = 1;
+int add(int a, int b) { return a + b + counter; }
+int start_kernel(void) { int x = 0; for (int i = 0; i < 10; i++) x = add(x, i); return x; }
@@ -4,20 +4,140 @@ extern crate xc_lib;
 
 use clap::{App, Arg};
 use rustyline::{error::ReadlineError, Editor};
+use std::convert::TryFrom;
+use std::fs;
+use std::process;
+use xc_lib::elf::SymbolTable;
+use xc_lib::encoding;
+use xc_lib::error::{Error, Result};
 use xc_lib::eval::{eval_expr, CompResult, Context};
-use xc_lib::show::PresentNum;
+use xc_lib::export;
+use xc_lib::import;
+use xc_lib::ioctl;
+use xc_lib::json;
+use xc_lib::layout::Layout;
+use xc_lib::net;
+use xc_lib::perm;
+use xc_lib::reprs::IntType;
+use xc_lib::rgb;
+use xc_lib::show::{ColorChoice, ExtraRow, Group, Grouping, PresentNum, ShowOpts};
+use xc_lib::signs::{self, Bias};
+use xc_lib::tables::{Table, TableKind};
+use xc_lib::template::{Radix, Template};
+use xc_lib::time;
+use xc_lib::unicode;
+use xc_lib::vaddr::{self, Arch};
+use xc_lib::wire;
 
-type OutputFn<'a> = Box<dyn Fn(i128) -> String + 'a>;
+type OutputFn<'a> = Box<dyn Fn(i128, Option<IntType>) -> Result<String> + 'a>;
 
-fn proc_expr(expr: &str, ctx: &mut Context, out_fns: &[&OutputFn]) {
-    match eval_expr(expr, ctx) {
-        Ok(Some(CompResult::Num(res))) => {
-            if !out_fns.is_empty() {
-                for out_fn in out_fns {
-                    println!("{}", out_fn(res));
-                }
+struct Session<'a> {
+    ctx: Context,
+    out_fns: Vec<&'a OutputFn<'a>>,
+    widths: bool,
+    json: bool,
+    vaddr: Option<Arch>,
+    net: bool,
+    perm: bool,
+    unicode: bool,
+    rgb: bool,
+    wire: bool,
+    signs: Option<Bias>,
+    time: Option<time::Unit>,
+    opts: ShowOpts,
+}
+
+fn show_num(res: i128, ty: Option<IntType>, session: &Session) {
+    if session.widths {
+        println!("{}", res.show_widths_with(&session.opts));
+    } else if !session.out_fns.is_empty() {
+        for out_fn in &session.out_fns {
+            match out_fn(res, ty) {
+                Ok(out) => println!("{}", out),
+                Err(err) => eprintln!("Error: {}", err),
+            }
+        }
+    } else {
+        let addr = ty.map_or(res, |ty| ty.raw(res) as i128);
+        let mut extra = u64::try_from(addr)
+            .ok()
+            .and_then(|addr| session.ctx.symbols().annotate(addr))
+            .map(|sym| ExtraRow::Label(String::from("Sym"), sym))
+            .into_iter()
+            .collect::<Vec<ExtraRow>>();
+        if let (Some(arch), Some(addr)) = (session.vaddr, vaddr::address(addr)) {
+            extra.push(ExtraRow::Label(String::from("VA"), arch.describe(addr)));
+        }
+        if let Some(unit) = session.time {
+            extra.extend(
+                time::describe(addr, unit)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        if let Some(mode) = Some(addr).filter(|_| session.perm).and_then(perm::show) {
+            extra.push(ExtraRow::Label(String::from("Perm"), mode));
+        }
+        if session.unicode {
+            extra.extend(
+                unicode::describe(addr)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        if session.rgb {
+            extra.extend(
+                rgb::describe(addr, session.opts.color)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        if session.net {
+            extra.extend(
+                net::describe(addr)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        if let Some(bias) = session.signs {
+            let width = ty.unwrap_or_else(|| IntType::fitting(res));
+            extra.extend(
+                signs::describe(res, width, bias)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        if session.wire {
+            extra.extend(
+                wire::describe(addr)
+                    .into_iter()
+                    .map(|(name, text)| ExtraRow::Label(name.to_string(), text)),
+            );
+        }
+        println!("{}", res.show_extra(ty, &extra, &session.opts));
+    }
+}
+
+fn proc_expr(expr: &str, session: &mut Session) {
+    let res = eval_expr(expr, &mut session.ctx);
+    if session.json {
+        println!("{}", json::to_json(expr, &res));
+        return;
+    }
+    match res {
+        Ok(Some(CompResult::Num(res))) => show_num(res, None, session),
+        Ok(Some(CompResult::Typed(res, ty))) => show_num(res, Some(ty), session),
+        Ok(Some(CompResult::Decoded(res, layout))) => {
+            if session.widths || !session.out_fns.is_empty() {
+                show_num(res, None, session)
             } else {
-                println!("{}", res.show_all());
+                let extra = Some(&layout)
+                    .filter(|layout| **layout == ioctl::layout())
+                    .and_then(|_| ioctl::describe(res))
+                    .map(|ioc| ExtraRow::Label(String::from("ioctl"), ioc))
+                    .into_iter()
+                    .collect::<Vec<ExtraRow>>();
+                println!("{}", res.show_fields_with(&layout, &extra, &session.opts));
             }
         }
         Ok(Some(comp_res)) => {
@@ -28,6 +148,171 @@ fn proc_expr(expr: &str, ctx: &mut Context, out_fns: &[&OutputFn]) {
     };
 }
 
+fn set_group(grouping: &mut Grouping, parts: &[&str]) -> Result<()> {
+    match parts {
+        [base, size] => grouping.set(base, size, None),
+        [base, size, sep] => grouping.set(base, size, Some(sep)),
+        _ => Err(Error::OptionParseError(parts.join(" "))),
+    }
+}
+
+// Defines `NAME { FIELD[hi:lo], ... }`, or every register in a TOML file
+fn define_reg(def: &str, ctx: &mut Context) -> Result<()> {
+    let layouts = if def.contains('{') {
+        vec![Layout::parse(def)?]
+    } else {
+        let text = fs::read_to_string(def).map_err(|_| Error::FileReadError(def.to_string()))?;
+        Layout::parse_toml(&text)?
+    };
+    for layout in layouts {
+        ctx.define(CompResult::Layout(layout));
+    }
+    Ok(())
+}
+
+fn import_file(path: &str, ctx: &mut Context) -> Result<()> {
+    let text = fs::read_to_string(path).map_err(|_| Error::FileReadError(path.to_string()))?;
+    let (imported, skipped) = import::import(&text, ctx);
+    eprintln!("Imported {} constants from {}", imported.len(), path);
+    if !skipped.is_empty() {
+        eprintln!("Skipped {}", skipped.join(", "));
+    }
+    Ok(())
+}
+
+// Writes the variables for `lang [radix] [file]`, to stdout if no file is given
+fn export_vars(args: &[&str], ctx: &Context) -> Result<()> {
+    let (lang, rest) = match args.split_first() {
+        Some((lang, rest)) => (export::Lang::parse(lang)?, rest),
+        None => return Err(Error::OptionParseError(String::new())),
+    };
+    let (radix, rest) = match rest.split_first() {
+        Some((radix, rest)) if Radix::parse(radix).is_some() => {
+            (Radix::parse(radix).unwrap(), rest)
+        }
+        _ => (Radix::Hex, rest),
+    };
+    let defs = export::export(ctx, lang, radix);
+    match rest {
+        [] => print!("{}", defs),
+        [file] => fs::write(file, defs).map_err(|_| Error::FileWriteError(file.to_string()))?,
+        _ => return Err(Error::OptionParseError(rest.join(" "))),
+    }
+    Ok(())
+}
+
+fn load_elf(path: &str, ctx: &mut Context) -> Result<()> {
+    let data = fs::read(path).map_err(|_| Error::FileReadError(path.to_string()))?;
+    let table = SymbolTable::parse(&data)?;
+    eprintln!("Loaded {} symbols from {}", table.len(), path);
+    ctx.load_symbols(table);
+    Ok(())
+}
+
+fn proc_command(cmd: &str, session: &mut Session) -> Result<()> {
+    let (name, args) = cmd.split_at(cmd.find(char::is_whitespace).unwrap_or(cmd.len()));
+    match name {
+        "widths" if args.trim().is_empty() => session.widths = !session.widths,
+        "widths" => {
+            let widths = session.widths;
+            session.widths = true;
+            proc_expr(args, session);
+            session.widths = widths;
+        }
+        "group" if args.trim().is_empty() => {
+            let grouping = session.opts.grouping;
+            let show = |name, group: Group| println!("{} {} '{}'", name, group.size, group.sep);
+            show("dec", grouping.dec);
+            show("hex", grouping.hex);
+            show("bin", grouping.bin);
+        }
+        "group" => {
+            let args = args.split_whitespace().collect::<Vec<&str>>();
+            set_group(&mut session.opts.grouping, &args)?;
+        }
+        "color" => session.opts.color = ColorChoice::parse(args.trim())?.enabled(),
+        "theme" => session.opts.theme.set(args)?,
+        "reg" if args.trim().is_empty() => {
+            for def in session.ctx.defs() {
+                if let CompResult::Layout(layout) = def {
+                    println!("{}", layout);
+                }
+            }
+        }
+        "reg" => define_reg(args.trim(), &mut session.ctx)?,
+        "flags" | "enum" => {
+            let kind = if name == "flags" {
+                TableKind::Flags
+            } else {
+                TableKind::Enum
+            };
+            if args.trim().is_empty() {
+                for def in session.ctx.defs() {
+                    match def {
+                        CompResult::Table(table) if table.kind == kind => println!("{}", table),
+                        _ => {}
+                    }
+                }
+            } else {
+                let table = Table::parse(kind, args)?;
+                session.ctx.define(CompResult::Table(table));
+            }
+        }
+        "import" => import_file(args.trim(), &mut session.ctx)?,
+        "export" => {
+            let args = args.split_whitespace().collect::<Vec<&str>>();
+            export_vars(&args, &session.ctx)?;
+        }
+        "vaddr" => match args.trim() {
+            "" => session.vaddr = None,
+            arch => session.vaddr = Some(Arch::parse(arch)?),
+        },
+        "net" => session.net = !session.net,
+        "perm" => session.perm = !session.perm,
+        "unicode" => session.unicode = !session.unicode,
+        "rgb" => session.rgb = !session.rgb,
+        "wire" => session.wire = !session.wire,
+        "signs" => match args.trim() {
+            "" if session.signs.is_some() => session.signs = None,
+            "" => session.signs = Some(Bias::Half),
+            bias => session.signs = Some(Bias::parse(bias)?),
+        },
+        "time" => match args.trim() {
+            "" if session.time.is_some() => session.time = None,
+            "" => session.time = Some(time::Unit::Auto),
+            unit => session.time = Some(time::Unit::parse(unit)?),
+        },
+        "load-elf" => load_elf(args.trim(), &mut session.ctx)?,
+        _ => return Err(Error::CommandParseError(name.to_string())),
+    }
+    Ok(())
+}
+
+fn proc_line(line: &str, session: &mut Session) {
+    // `::1` is an IPv6 address rather than a command
+    match line.trim().strip_prefix(':') {
+        Some(cmd) if !cmd.starts_with(':') => {
+            if let Err(err) = proc_command(cmd, session) {
+                if session.json {
+                    println!("{}", json::to_json(line, &Err(err)));
+                } else {
+                    eprintln!("Error: {}", err);
+                }
+            }
+        }
+        _ => proc_expr(line, session),
+    }
+}
+
+// Prints the bytes shown in the BE row through a text encoding
+fn text_output<'a>(encode: fn(&[u8]) -> String) -> OutputFn<'a> {
+    Box::new(move |res, ty| {
+        Ok(encode(
+            &ty.unwrap_or_else(|| IntType::fitting(res)).to_bytes(res),
+        ))
+    })
+}
+
 fn main() {
     let matches = App::new("xc")
         .version("0.1.0")
@@ -47,6 +332,141 @@ fn main() {
                 .short("b")
                 .help("Only print binary output"),
         )
+        .arg(
+            Arg::with_name("b64")
+                .long("b64")
+                .help("Only print the big-endian bytes as Base64"),
+        )
+        .arg(
+            Arg::with_name("b32")
+                .long("b32")
+                .help("Only print the big-endian bytes as Base32"),
+        )
+        .arg(
+            Arg::with_name("b58")
+                .long("b58")
+                .help("Only print the big-endian bytes as Base58"),
+        )
+        .arg(
+            Arg::with_name("hexstr")
+                .long("hexstr")
+                .help("Only print the big-endian bytes as a hex string"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .takes_value(true)
+                .value_name("template")
+                .help("Print output through a template such as 0x{hex:016}, or a c, rust or python preset"),
+        )
+        .arg(
+            Arg::with_name("widths")
+                .long("widths")
+                .help("Print the value as read by every standard integer width"),
+        )
+        .arg(
+            Arg::with_name("group")
+                .long("group")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("base:size[:sep]")
+                .help("Group the digits of dec, hex or bin output, e.g. hex:4 or dec:3:_"),
+        )
+        .arg(
+            Arg::with_name("regs")
+                .long("regs")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("file")
+                .help("Load register layouts from a TOML file, to be used with decode"),
+        )
+        .arg(
+            Arg::with_name("import")
+                .long("import")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("file")
+                .help("Import #define, enum and const definitions of a C header or Rust source as $variables"),
+        )
+        .arg(
+            Arg::with_name("elf")
+                .long("elf")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("file")
+                .help("Load the symbols of an ELF binary, for sym() and annotating addresses"),
+        )
+        .arg(
+            Arg::with_name("vaddr")
+                .long("vaddr")
+                .takes_value(true)
+                .possible_values(&["x86_64", "aarch64"])
+                .help("Show the page table indices and page offset of the value as a virtual address"),
+        )
+        .arg(
+            Arg::with_name("net")
+                .long("net")
+                .help("Show the value as an IPv4, MAC and IPv6 address"),
+        )
+        .arg(
+            Arg::with_name("perm")
+                .long("perm")
+                .help("Show the value as Unix permission bits, like rwxr-x--- (0750)"),
+        )
+        .arg(
+            Arg::with_name("unicode")
+                .long("unicode")
+                .help("Show the value as a Unicode character with its UTF-8 and UTF-16 encodings"),
+        )
+        .arg(
+            Arg::with_name("rgb")
+                .long("rgb")
+                .help("Show the channels of the value as an RGB or RGBA color, with a swatch"),
+        )
+        .arg(
+            Arg::with_name("wire")
+                .long("wire")
+                .help("Show the value encoded as a protobuf varint, a ZigZag varint and signed LEB128"),
+        )
+        .arg(
+            Arg::with_name("signs")
+                .long("signs")
+                .takes_value(true)
+                .value_name("half|K")
+                .help("Show the value in ones' complement, sign-magnitude and excess-K at its width"),
+        )
+        .arg(
+            Arg::with_name("time")
+                .long("time")
+                .takes_value(true)
+                .possible_values(&["auto", "s", "ms", "us", "ns"])
+                .value_name("unit")
+                .help("Show the value as a UTC time and a duration, counted in the given unit"),
+        )
+        .arg(
+            Arg::with_name("json")
+                .long("json")
+                .help("Print each result, or error, as a line of JSON"),
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .takes_value(true)
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .help("When to color the output, auto colors it for a terminal unless NO_COLOR is set"),
+        )
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .takes_value(true)
+                .value_name("row=color,...")
+                .help("Colors of the dec, hex, bin, ruler and bytes rows, e.g. dec=cyan,ruler=bright-black"),
+        )
         .arg(
             Arg::with_name("expr")
                 .multiple(true)
@@ -55,12 +475,36 @@ fn main() {
         )
         .get_matches();
 
-    let possible_outputs: [(&str, OutputFn); 3] = [
-        ("dec", Box::new(|res| res.as_dec(true))),
-        ("hex", Box::new(|res| res.as_hex(true))),
-        ("bin", Box::new(|res| res.as_bin(true).0)),
+    let template = matches.value_of("format").map(|format| {
+        Template::parse(format).unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        })
+    });
+
+    let mut possible_outputs: Vec<(&str, OutputFn)> = vec![
+        (
+            "dec",
+            Box::new(|res, ty| Ok(ty.map_or_else(|| res.as_dec(true), |ty| ty.fmt_dec(res)))),
+        ),
+        (
+            "hex",
+            Box::new(|res, ty| Ok(ty.map_or(res, |ty| ty.raw(res) as i128).as_hex(true))),
+        ),
+        (
+            "bin",
+            Box::new(|res, ty| Ok(ty.map_or(res, |ty| ty.raw(res) as i128).as_bin(true).0)),
+        ),
+        ("b64", text_output(encoding::base64)),
+        ("b32", text_output(encoding::base32)),
+        ("b58", text_output(encoding::base58)),
+        ("hexstr", text_output(encoding::hex)),
     ];
 
+    if let Some(template) = &template {
+        possible_outputs.push(("format", Box::new(move |res, ty| template.render(res, ty))));
+    }
+
     let mut selected = possible_outputs
         .iter()
         .filter_map(|(name, output)| matches.index_of(name).map(|idx| (idx, output)))
@@ -68,30 +512,93 @@ fn main() {
 
     selected.sort_by_key(|(idx, _)| *idx);
 
-    let selected_ord = selected
-        .into_iter()
-        .map(|(_, out_fn)| out_fn)
-        .collect::<Vec<&OutputFn>>();
+    let mut session = Session {
+        ctx: Context::new(),
+        out_fns: selected.into_iter().map(|(_, out_fn)| out_fn).collect(),
+        widths: matches.is_present("widths"),
+        json: matches.is_present("json"),
+        net: matches.is_present("net"),
+        perm: matches.is_present("perm"),
+        unicode: matches.is_present("unicode"),
+        rgb: matches.is_present("rgb"),
+        wire: matches.is_present("wire"),
+        signs: None,
+        time: matches
+            .value_of("time")
+            .map(|unit| time::Unit::parse(unit).unwrap()),
+        vaddr: matches
+            .value_of("vaddr")
+            .map(|arch| Arch::parse(arch).unwrap()),
+        opts: ShowOpts::default(),
+    };
+
+    if let Some(color) = matches.value_of("color") {
+        session.opts.color = ColorChoice::parse(color).unwrap().enabled();
+    }
+
+    if let Some(bias) = matches.value_of("signs") {
+        match Bias::parse(bias) {
+            Ok(bias) => session.signs = Some(bias),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(theme) = matches.value_of("theme") {
+        if let Err(err) = session.opts.theme.set(theme) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    }
+
+    for spec in matches.values_of("group").into_iter().flatten() {
+        let parts = spec.splitn(3, ':').collect::<Vec<&str>>();
+        if let Err(err) = set_group(&mut session.opts.grouping, &parts) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    }
+
+    for file in matches.values_of("regs").into_iter().flatten() {
+        if let Err(err) = define_reg(file, &mut session.ctx) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    }
+
+    for file in matches.values_of("import").into_iter().flatten() {
+        if let Err(err) = import_file(file, &mut session.ctx) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    }
+
+    for file in matches.values_of("elf").into_iter().flatten() {
+        if let Err(err) = load_elf(file, &mut session.ctx) {
+            eprintln!("Error: {}", err);
+            process::exit(1);
+        }
+    }
 
     if let Some(exprs) = matches.values_of("expr") {
         let exprs: &str = &exprs.collect::<Vec<&str>>().join(" ");
-        let mut ctx = Context::new();
         for expr in exprs.split(';') {
             if !expr.trim().is_empty() {
-                if selected_ord.is_empty() {
+                if session.out_fns.is_empty() && !session.json {
                     println!("> {}", expr.trim());
                 }
-                proc_expr(expr, &mut ctx, &selected_ord);
+                proc_line(expr, &mut session);
             }
         }
     } else {
         let mut editor = Editor::<()>::new();
-        let mut ctx = Context::new();
         loop {
             match editor.readline(">> ") {
                 Ok(buf) => {
                     if !buf.trim().is_empty() {
-                        proc_expr(&buf, &mut ctx, &selected_ord);
+                        proc_line(&buf, &mut session);
                         println!();
                     }
                     editor.add_history_entry(buf);
@@ -0,0 +1,60 @@
+pub fn pdep(x: u128, mask: u128) -> u128 {
+    let mut res = 0;
+    let mut src = x;
+    for i in (0..128).filter(|i| mask >> i & 1 == 1) {
+        res |= (src & 1) << i;
+        src >>= 1;
+    }
+    res
+}
+
+pub fn pext(x: u128, mask: u128) -> u128 {
+    (0..128)
+        .filter(|i| mask >> i & 1 == 1)
+        .enumerate()
+        .fold(0, |res, (dst, src)| res | (x >> src & 1) << dst)
+}
+
+// Morton / Z-order encoding, `x` goes to the even bits and `y` to the odd ones
+pub fn interleave(x: u64, y: u64) -> u128 {
+    const EVEN: u128 = 0x5555_5555_5555_5555_5555_5555_5555_5555;
+    pdep(x.into(), EVEN) | pdep(y.into(), EVEN << 1)
+}
+
+pub fn deinterleave(z: u128) -> (u64, u64) {
+    const EVEN: u128 = 0x5555_5555_5555_5555_5555_5555_5555_5555;
+    (pext(z, EVEN) as u64, pext(z, EVEN << 1) as u64)
+}
+
+// Bit `i` of the result is bit `idx[i]` of `x`
+pub fn permute(x: u128, idx: &[u32]) -> u128 {
+    idx.iter()
+        .enumerate()
+        .fold(0, |res, (dst, &src)| res | (x >> src & 1) << dst)
+}
+
+#[test]
+fn test_pdep_pext() {
+    assert_eq!(pdep(0b101, 0b11010), 0b10010);
+    assert_eq!(pext(0b10010, 0b11010), 0b101);
+    assert_eq!(pdep(u128::MAX, 0xf0f0), 0xf0f0);
+    assert_eq!(pext(0xdead_beef, 0xffff_0000), 0xdead);
+    assert_eq!(pdep(0, u128::MAX), 0);
+    assert_eq!(pext(1 << 127, 1 << 127), 1);
+}
+
+#[test]
+fn test_interleave() {
+    assert_eq!(interleave(0b11, 0), 0b0101);
+    assert_eq!(interleave(0, 0b11), 0b1010);
+    assert_eq!(interleave(5, 9), 0b1001_0011);
+    assert_eq!(deinterleave(0b1001_0011), (5, 9));
+    assert_eq!(deinterleave(interleave(u64::MAX, 0)), (u64::MAX, 0));
+}
+
+#[test]
+fn test_permute() {
+    assert_eq!(permute(0b0001, &[3, 2, 1, 0]), 0b1000);
+    assert_eq!(permute(0b1010_0000, &[5, 7]), 0b11);
+    assert_eq!(permute(0xff, &[]), 0);
+}
@@ -0,0 +1,728 @@
+use crate::bits;
+use crate::checksum::{self, CrcParams};
+use crate::encoding;
+use crate::error::{Error, Result};
+use crate::eval::{CompResult, Context};
+use crate::ioctl;
+use crate::reprs::IntType;
+use crate::rgb;
+use crate::signs::{self, Bias};
+use crate::unicode;
+use crate::uuid;
+use crate::vaddr::{self, Arch};
+use crate::wire;
+use std::convert::TryFrom;
+
+type BuiltinFn = fn(&[CompResult]) -> Option<CompResult>;
+
+const BUILTINS: &[(&str, BuiltinFn)] = &[
+    ("crc", crc),
+    ("crc32", |args| crc_preset(args, &checksum::CRC32)),
+    ("crc32c", |args| crc_preset(args, &checksum::CRC32C)),
+    ("crc16_ccitt", |args| {
+        crc_preset(args, &checksum::CRC16_CCITT)
+    }),
+    ("crc8", |args| crc_preset(args, &checksum::CRC8)),
+    ("adler32", |args| hash(args, checksum::adler32)),
+    ("fletcher16", |args| hash(args, checksum::fletcher16)),
+    ("fnv1a32", |args| hash(args, checksum::fnv1a32)),
+    ("fnv1a64", |args| hash(args, checksum::fnv1a64)),
+    ("inet_csum", |args| hash(args, checksum::inet_csum)),
+    ("pdep", |args| binary(args, bits::pdep)),
+    ("pext", |args| binary(args, bits::pext)),
+    ("interleave", interleave),
+    ("deinterleave", deinterleave),
+    ("permute", permute),
+    ("sext", |args| extend(args, true)),
+    ("zext", |args| extend(args, false)),
+    ("trunc", |args| extend(args, false)),
+    ("to_ones", |args| at_width(args, signs::to_ones)),
+    ("from_ones", |args| at_width(args, signs::from_ones)),
+    ("to_sign_mag", |args| {
+        at_width(args, signs::to_sign_magnitude)
+    }),
+    ("from_sign_mag", |args| {
+        at_width(args, signs::from_sign_magnitude)
+    }),
+    ("to_excess", |args| excess(args, signs::to_excess)),
+    ("from_excess", |args| excess(args, signs::from_excess)),
+    ("bswap16", |args| bswap(args, 16)),
+    ("bswap32", |args| bswap(args, 32)),
+    ("bswap64", |args| bswap(args, 64)),
+    ("to_le", |args| to_endian(args, cfg!(target_endian = "big"))),
+    ("to_be", |args| {
+        to_endian(args, cfg!(target_endian = "little"))
+    }),
+    ("bytes", bytes),
+    ("decode", decode),
+    ("b64enc", |args| encode_text(args, encoding::base64)),
+    ("b64dec", |args| decode_text(args, encoding::from_base64)),
+    ("b32enc", |args| encode_text(args, encoding::base32)),
+    ("b32dec", |args| decode_text(args, encoding::from_base32)),
+    ("b58enc", |args| encode_text(args, encoding::base58)),
+    ("b58dec", |args| decode_text(args, encoding::from_base58)),
+    ("hexenc", |args| encode_text(args, encoding::hex)),
+    ("hexdec", |args| decode_text(args, encoding::from_hex)),
+    ("vaddr", vaddr),
+    ("uuid_version", |args| match args {
+        [x] => Some(CompResult::Num(uuid::version(x.as_num()?))),
+        _ => None,
+    }),
+    ("uuid_variant", |args| match args {
+        [x] => {
+            let (bits, name) = uuid::variant(x.as_num()?);
+            Some(CompResult::Named(bits, name.to_string()))
+        }
+        _ => None,
+    }),
+    ("utf8", |args| match args {
+        [x] => Some(CompResult::Bytes(unicode::utf8(unicode::scalar(
+            x.as_num()?,
+        )?))),
+        _ => None,
+    }),
+    ("from_utf8", from_utf8),
+    ("rgb565", |args| match args {
+        [rgb] => {
+            let rgb = num(rgb)?;
+            let rgb565 = rgb::rgb565(rgb >> 16, (rgb >> 8) & 0xff, rgb & 0xff);
+            Some(CompResult::Num(rgb565.filter(|_| rgb >> 24 == 0)?))
+        }
+        [r, g, b] => Some(CompResult::Num(rgb::rgb565(num(r)?, num(g)?, num(b)?)?)),
+        _ => None,
+    }),
+    ("unpack_rgb565", |args| match args {
+        [x] => {
+            let (r, g, b) = rgb::unpack_rgb565(num(x)?)?;
+            Some(CompResult::Tuple(vec![
+                CompResult::Num(r),
+                CompResult::Num(g),
+                CompResult::Num(b),
+            ]))
+        }
+        _ => None,
+    }),
+    ("argb8888", |args| match args {
+        [r, g, b] => Some(CompResult::Num(rgb::argb8888(
+            0xff,
+            num(r)?,
+            num(g)?,
+            num(b)?,
+        )?)),
+        [a, r, g, b] => Some(CompResult::Num(rgb::argb8888(
+            num(a)?,
+            num(r)?,
+            num(g)?,
+            num(b)?,
+        )?)),
+        _ => None,
+    }),
+    ("varint", |args| match args {
+        [x] => Some(CompResult::Bytes(wire::varint(num(x)?)?)),
+        _ => None,
+    }),
+    ("from_varint", |args| decode_bytes(args, wire::from_varint)),
+    ("uleb128", |args| match args {
+        [x] => Some(CompResult::Bytes(wire::uleb128(unsigned(x)?))),
+        _ => None,
+    }),
+    ("from_uleb128", |args| {
+        decode_bytes(args, |b| i128::try_from(wire::from_uleb128(b)?).ok())
+    }),
+    ("sleb128", |args| match args {
+        [x] => Some(CompResult::Bytes(wire::sleb128(num(x)?))),
+        _ => None,
+    }),
+    ("from_sleb128", |args| {
+        decode_bytes(args, wire::from_sleb128)
+    }),
+    ("zigzag", |args| match args {
+        [x] => Some(CompResult::Num(i128::try_from(wire::zigzag(num(x)?)).ok()?)),
+        _ => None,
+    }),
+    ("from_zigzag", |args| match args {
+        [x] => Some(CompResult::Num(wire::from_zigzag(unsigned(x)?))),
+        _ => None,
+    }),
+    ("to_bcd", |args| unary(args, wire::bcd)),
+    ("from_bcd", |args| unary(args, wire::from_bcd)),
+    ("gray", |args| unary(args, |x| Some(wire::gray(x)))),
+    ("from_gray", |args| {
+        unary(args, |x| Some(wire::from_gray(x)))
+    }),
+    ("_IO", |args| ioc(args, ioctl::NONE)),
+    ("_IOR", |args| ioc(args, ioctl::READ)),
+    ("_IOW", |args| ioc(args, ioctl::WRITE)),
+    ("_IOWR", |args| ioc(args, ioctl::READ | ioctl::WRITE)),
+];
+
+type ContextFn = fn(&Context, &[CompResult]) -> Option<CompResult>;
+
+// Builtins that read definitions loaded into the context
+const CONTEXT_BUILTINS: &[(&str, ContextFn)] = &[("sym", sym)];
+
+pub fn lookup(name: &str) -> Option<&'static str> {
+    BUILTINS
+        .iter()
+        .map(|(n, _)| *n)
+        .chain(CONTEXT_BUILTINS.iter().map(|(n, _)| *n))
+        .find(|&n| n == name)
+}
+
+pub fn call(name: &str, args: &[CompResult]) -> Result<CompResult> {
+    BUILTINS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .and_then(|(_, f)| f(args))
+        .ok_or_else(|| Error::FnArgsError(name.to_string()))
+}
+
+pub fn call_with_ctx(name: &str, args: &[CompResult], ctx: &Context) -> Result<CompResult> {
+    match CONTEXT_BUILTINS.iter().find(|(n, _)| *n == name) {
+        Some((_, f)) => f(ctx, args).ok_or_else(|| Error::FnArgsError(name.to_string())),
+        None => call(name, args),
+    }
+}
+
+fn num(arg: &CompResult) -> Option<i128> {
+    match arg {
+        CompResult::Num(i) => Some(*i),
+        _ => None,
+    }
+}
+
+// Numbers are taken as their shortest big-endian encoding, negative ones as all 16 bytes
+fn data(arg: &CompResult) -> Option<Vec<u8>> {
+    match arg {
+        CompResult::Num(i) => {
+            let bytes = i.to_be_bytes();
+            let skip = if *i < 0 {
+                0
+            } else {
+                bytes.iter().take_while(|&&b| b == 0).count().min(15)
+            };
+            Some(bytes[skip..].to_vec())
+        }
+        CompResult::Bytes(bytes) => Some(bytes.clone()),
+        _ => None,
+    }
+}
+
+fn hash<T: Into<i128>>(args: &[CompResult], f: fn(&[u8]) -> T) -> Option<CompResult> {
+    match args {
+        [arg] => Some(CompResult::Num(f(&data(arg)?).into())),
+        _ => None,
+    }
+}
+
+fn encode_text(args: &[CompResult], f: fn(&[u8]) -> String) -> Option<CompResult> {
+    match args {
+        [arg] => Some(CompResult::Bytes(f(&data(arg)?).into_bytes())),
+        _ => None,
+    }
+}
+
+// Decoded bytes are read as a big-endian number when they fit in one
+fn decode_text(args: &[CompResult], f: fn(&str) -> Option<Vec<u8>>) -> Option<CompResult> {
+    let bytes = match args {
+        [CompResult::Bytes(text)] => f(std::str::from_utf8(text).ok()?)?,
+        _ => return None,
+    };
+    if (1..=16).contains(&bytes.len()) {
+        let x = bytes.iter().fold(0u128, |x, &b| x << 8 | u128::from(b));
+        Some(CompResult::Num(x as i128))
+    } else {
+        Some(CompResult::Bytes(bytes))
+    }
+}
+
+fn unsigned(arg: &CompResult) -> Option<u128> {
+    u128::try_from(num(arg)?).ok()
+}
+
+// Applies `f` to a single non-negative number
+fn unary(args: &[CompResult], f: fn(u128) -> Option<u128>) -> Option<CompResult> {
+    match args {
+        [x] => Some(CompResult::Num(i128::try_from(f(unsigned(x)?)?).ok()?)),
+        _ => None,
+    }
+}
+
+// Decodes a byte sequence given as a string or a number
+fn decode_bytes(args: &[CompResult], f: fn(&[u8]) -> Option<i128>) -> Option<CompResult> {
+    match args {
+        [bytes] => Some(CompResult::Num(f(&data(bytes)?)?)),
+        _ => None,
+    }
+}
+
+fn binary(args: &[CompResult], f: fn(u128, u128) -> u128) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x), CompResult::Num(y)] => {
+            Some(CompResult::Num(f(*x as u128, *y as u128) as i128))
+        }
+        _ => None,
+    }
+}
+
+fn extend(args: &[CompResult], signed: bool) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x), CompResult::Num(bits)] => {
+            let ty = IntType::new(u32::try_from(*bits).ok()?, signed)?;
+            Some(CompResult::Num(ty.wrap(*x)))
+        }
+        _ => None,
+    }
+}
+
+fn at_width(args: &[CompResult], f: fn(i128, u32) -> Option<i128>) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x), CompResult::Num(bits)] => {
+            Some(CompResult::Num(f(*x, u32::try_from(*bits).ok()?)?))
+        }
+        _ => None,
+    }
+}
+
+// Takes the bias K as an optional third argument, offset binary when it is left out
+fn excess(args: &[CompResult], f: fn(i128, u32, Bias) -> Option<i128>) -> Option<CompResult> {
+    let (x, bits, bias) = match args {
+        [CompResult::Num(x), CompResult::Num(bits)] => (*x, *bits, Bias::Half),
+        [CompResult::Num(x), CompResult::Num(bits), CompResult::Num(k)] => (*x, *bits, Bias::K(*k)),
+        _ => return None,
+    };
+    Some(CompResult::Num(f(x, u32::try_from(bits).ok()?, bias)?))
+}
+
+fn swap(x: i128, bits: u32) -> i128 {
+    let ty = IntType::new(bits, false).unwrap();
+    (ty.raw(x).swap_bytes() >> (128 - bits)) as i128
+}
+
+fn bswap(args: &[CompResult], bits: u32) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x)] => Some(CompResult::Num(swap(*x, bits))),
+        _ => None,
+    }
+}
+
+// Converts between host and the given byte order, either at the given width or the smallest
+// one that fits the value
+fn to_endian(args: &[CompResult], swapped: bool) -> Option<CompResult> {
+    let (x, ty) = match args {
+        [CompResult::Num(x)] => (*x, IntType::fitting(*x)),
+        [CompResult::Num(x), CompResult::Num(bits)] => {
+            (*x, IntType::new(u32::try_from(*bits).ok()?, false)?)
+        }
+        _ => return None,
+    };
+    let bits = ty.byte_len() as u32 * 8;
+    Some(CompResult::Num(if swapped {
+        swap(x, bits)
+    } else {
+        IntType::new(bits, false)?.raw(x) as i128
+    }))
+}
+
+fn bytes(args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x), CompResult::Num(n)] if (1..=16).contains(n) => {
+            let ty = IntType::new(*n as u32 * 8, false)?;
+            Some(CompResult::Bytes(ty.to_bytes(*x)))
+        }
+        _ => None,
+    }
+}
+
+fn decode(args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [CompResult::Layout(layout), x] => Some(CompResult::Decoded(x.as_num()?, layout.clone())),
+        [CompResult::Table(table), x] => {
+            let x = x.as_num()?;
+            Some(CompResult::Named(x, table.decode(x)))
+        }
+        _ => None,
+    }
+}
+
+// The page table indices and page offset of a canonical address, for x86_64 unless named
+fn vaddr(args: &[CompResult]) -> Option<CompResult> {
+    let (addr, arch) = match args {
+        [addr] => (addr, Arch::X86_64),
+        [addr, CompResult::Bytes(arch)] => {
+            (addr, Arch::parse(std::str::from_utf8(arch).ok()?).ok()?)
+        }
+        _ => return None,
+    };
+    let addr = vaddr::address(addr.as_num()?)?;
+    if !arch.is_canonical(addr) {
+        return None;
+    }
+    let (indices, offset) = arch.split(addr);
+    let mut items = indices
+        .iter()
+        .map(|&index| CompResult::Num(index.into()))
+        .collect::<Vec<CompResult>>();
+    items.push(CompResult::Num(offset.into()));
+    Some(CompResult::Tuple(items))
+}
+
+// The code point of a single character, or a tuple of them for a longer string
+fn from_utf8(args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [bytes] => {
+            let mut points = unicode::from_utf8(&data(bytes)?)?
+                .into_iter()
+                .map(CompResult::Num)
+                .collect::<Vec<CompResult>>();
+            match points.len() {
+                0 => None,
+                1 => points.pop(),
+                _ => Some(CompResult::Tuple(points)),
+            }
+        }
+        _ => None,
+    }
+}
+
+// `_IO(type, nr)` and `_IOR(type, nr, size)`, the type being a number or a character like "T"
+fn ioc(args: &[CompResult], dir: u32) -> Option<CompResult> {
+    let (ty, nr, size) = match (args, dir) {
+        ([ty, nr], ioctl::NONE) => (ty, nr, 0),
+        ([ty, nr, size], dir) if dir != ioctl::NONE => (ty, nr, num(size)?),
+        _ => return None,
+    };
+    let ty = match ty {
+        CompResult::Bytes(c) if c.len() == 1 => c[0].into(),
+        ty => num(ty)?,
+    };
+    Some(CompResult::Num(ioctl::encode(dir, ty, num(nr)?, size)?))
+}
+
+fn sym(ctx: &Context, args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [CompResult::Bytes(name)] => {
+            let name = std::str::from_utf8(name).ok()?;
+            let sym = ctx.symbols().lookup(name)?;
+            Some(CompResult::Num(sym.addr.into()))
+        }
+        _ => None,
+    }
+}
+
+fn crc(args: &[CompResult]) -> Option<CompResult> {
+    if let [data_arg, rest @ ..] = args {
+        let nums = rest.iter().map(num).collect::<Option<Vec<i128>>>()?;
+        if let [width, poly, init, refin, refout, xorout] = nums[..] {
+            if !(1..=64).contains(&width) {
+                return None;
+            }
+            let params = CrcParams {
+                width: width as u32,
+                poly: poly as u64,
+                init: init as u64,
+                refin: refin != 0,
+                refout: refout != 0,
+                xorout: xorout as u64,
+            };
+            return crc_preset(std::slice::from_ref(data_arg), &params);
+        }
+    }
+    None
+}
+
+fn crc_preset(args: &[CompResult], params: &CrcParams) -> Option<CompResult> {
+    match args {
+        [arg] => Some(CompResult::Num(checksum::crc(&data(arg)?, params).into())),
+        _ => None,
+    }
+}
+
+fn interleave(args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(x), CompResult::Num(y)] => {
+            let (x, y) = (u64::try_from(*x).ok()?, u64::try_from(*y).ok()?);
+            Some(CompResult::Num(bits::interleave(x, y) as i128))
+        }
+        _ => None,
+    }
+}
+
+fn deinterleave(args: &[CompResult]) -> Option<CompResult> {
+    match args {
+        [CompResult::Num(z)] => {
+            let (x, y) = bits::deinterleave(*z as u128);
+            Some(CompResult::Tuple(vec![
+                CompResult::Num(x.into()),
+                CompResult::Num(y.into()),
+            ]))
+        }
+        _ => None,
+    }
+}
+
+// The bit indices may be given either as separate arguments or as a tuple
+fn permute(args: &[CompResult]) -> Option<CompResult> {
+    if let [CompResult::Num(x), idx @ ..] = args {
+        let idx = idx
+            .iter()
+            .flat_map(|arg| match arg {
+                CompResult::Tuple(items) => items.clone(),
+                other => vec![other.clone()],
+            })
+            .map(|arg| num(&arg).filter(|i| (0..128).contains(i)).map(|i| i as u32))
+            .collect::<Option<Vec<u32>>>()?;
+        Some(CompResult::Num(bits::permute(*x as u128, &idx) as i128))
+    } else {
+        None
+    }
+}
+
+#[test]
+fn test_builtins() {
+    let s = |s: &str| CompResult::Bytes(s.as_bytes().to_vec());
+    let n = CompResult::Num;
+    assert_eq!(call("crc32", &[s("123456789")]).unwrap(), n(0xcbf4_3926));
+    assert_eq!(
+        call("crc32", &[n(0x31_3233_3435_3637_3839)]).unwrap(),
+        n(0xcbf4_3926)
+    );
+    assert_eq!(
+        call(
+            "crc",
+            &[s("123456789"), n(16), n(0x1021), n(0), n(1), n(1), n(0)]
+        )
+        .unwrap(),
+        n(0x2189)
+    );
+    assert_eq!(call("adler32", &[s("Wikipedia")]).unwrap(), n(0x11e6_0398));
+    assert_eq!(call("crc8", &[n(0)]).unwrap(), n(0));
+    assert!(call("crc32", &[]).is_err());
+    assert!(call("crc", &[s("1"), n(65), n(1), n(0), n(0), n(0), n(0)]).is_err());
+    assert_eq!(lookup("crc32c"), Some("crc32c"));
+    assert_eq!(lookup("nope"), None);
+}
+
+#[test]
+fn test_bit_builtins() {
+    let n = CompResult::Num;
+    assert_eq!(call("pdep", &[n(0b101), n(0b11010)]).unwrap(), n(0b10010));
+    assert_eq!(call("pext", &[n(0b10010), n(0b11010)]).unwrap(), n(0b101));
+    assert_eq!(call("interleave", &[n(5), n(9)]).unwrap(), n(0b1001_0011));
+    assert!(call("interleave", &[n(0x1_0000_0000_0000_0001), n(0)]).is_err());
+    assert!(call("interleave", &[n(-1), n(0)]).is_err());
+    assert_eq!(
+        call("deinterleave", &[n(0b1001_0011)]).unwrap(),
+        CompResult::Tuple(vec![n(5), n(9)])
+    );
+    assert_eq!(
+        call("permute", &[n(1), n(3), n(2), n(1), n(0)]).unwrap(),
+        n(0b1000)
+    );
+    assert_eq!(
+        call("permute", &[n(1), CompResult::Tuple(vec![n(1), n(0)])]).unwrap(),
+        n(0b10)
+    );
+    assert!(call("permute", &[n(1), n(128)]).is_err());
+    assert!(call("pdep", &[n(1)]).is_err());
+}
+
+#[test]
+fn test_ext_builtins() {
+    let n = CompResult::Num;
+    assert_eq!(call("sext", &[n(0xfff), n(12)]).unwrap(), n(-1));
+    assert_eq!(call("sext", &[n(0x7ff), n(12)]).unwrap(), n(0x7ff));
+    assert_eq!(call("zext", &[n(-1), n(16)]).unwrap(), n(0xffff));
+    assert_eq!(call("trunc", &[n(0x12345), n(8)]).unwrap(), n(0x45));
+    assert!(call("sext", &[n(1), n(0)]).is_err());
+    assert!(call("sext", &[n(1), n(129)]).is_err());
+}
+
+#[test]
+fn test_byte_order_builtins() {
+    let n = CompResult::Num;
+    assert_eq!(call("bswap16", &[n(0x1234)]).unwrap(), n(0x3412));
+    assert_eq!(call("bswap32", &[n(0x1234)]).unwrap(), n(0x3412_0000));
+    assert_eq!(call("bswap64", &[n(-1)]).unwrap(), n(0xffff_ffff_ffff_ffff));
+    let (be, le) = if cfg!(target_endian = "little") {
+        (n(0x3412), n(0x1234))
+    } else {
+        (n(0x1234), n(0x3412))
+    };
+    assert_eq!(call("to_be", &[n(0x1234)]).unwrap(), be);
+    assert_eq!(call("to_le", &[n(0x1234)]).unwrap(), le);
+    assert_eq!(
+        call("to_be", &[n(0x12), n(32)]).unwrap(),
+        if cfg!(target_endian = "little") {
+            n(0x1200_0000)
+        } else {
+            n(0x12)
+        }
+    );
+    assert_eq!(
+        call("bytes", &[n(0x4142), n(3)]).unwrap(),
+        CompResult::Bytes(vec![0, 0x41, 0x42])
+    );
+    assert!(call("bytes", &[n(1), n(17)]).is_err());
+}
+
+#[test]
+fn test_vaddr_builtin() {
+    let n = CompResult::Num;
+    let s = |s: &str| CompResult::Bytes(s.as_bytes().to_vec());
+    assert_eq!(
+        call("vaddr", &[n(0xffff_ffff_8100_0010)]).unwrap(),
+        CompResult::Tuple(vec![n(511), n(510), n(8), n(0), n(0x10)])
+    );
+    assert_eq!(
+        call("vaddr", &[n(0x8000_0000_1000), s("aarch64")]).unwrap(),
+        CompResult::Tuple(vec![n(256), n(0), n(0), n(1), n(0)])
+    );
+    assert!(call("vaddr", &[n(0x8000_0000_1000)]).is_err());
+    assert!(call("vaddr", &[n(0), s("riscv")]).is_err());
+}
+
+#[test]
+fn test_ioctl_builtins() {
+    let n = CompResult::Num;
+    let s = |s: &str| CompResult::Bytes(s.as_bytes().to_vec());
+    assert_eq!(
+        call("_IOR", &[s("T"), n(0x2a), n(44)]).unwrap(),
+        n(0x802c_542a)
+    );
+    assert_eq!(call("_IO", &[n(0x54), n(0x13)]).unwrap(), n(0x5413));
+    assert_eq!(
+        call("_IOWR", &[s("A"), n(1), n(8)]).unwrap(),
+        n(0xc008_4101)
+    );
+    assert!(call("_IO", &[s("T"), n(1), n(8)]).is_err());
+    assert!(call("_IOW", &[s("TT"), n(1), n(8)]).is_err());
+    assert!(call("_IOW", &[s("T"), n(0x100), n(8)]).is_err());
+}
+
+#[test]
+fn test_uuid_builtins() {
+    let x = CompResult::Num(0x123e_4567_e89b_12d3_a456_4266_1417_4000);
+    assert_eq!(
+        call("uuid_version", std::slice::from_ref(&x)).unwrap(),
+        CompResult::Num(1)
+    );
+    assert_eq!(
+        call("uuid_variant", &[x]).unwrap(),
+        CompResult::Named(2, String::from("RFC 9562"))
+    );
+    assert!(call("uuid_version", &[]).is_err());
+}
+
+#[test]
+fn test_unicode_builtins() {
+    let n = CompResult::Num;
+    let s = |s: &str| CompResult::Bytes(s.as_bytes().to_vec());
+    assert_eq!(call("utf8", &[n(0x1f600)]).unwrap(), s("😀"));
+    assert_eq!(call("from_utf8", &[s("😀")]).unwrap(), n(0x1f600));
+    assert_eq!(call("from_utf8", &[n(0xc3a9)]).unwrap(), n(0xe9));
+    assert_eq!(
+        call("from_utf8", &[s("ab")]).unwrap(),
+        CompResult::Tuple(vec![n(0x61), n(0x62)])
+    );
+    assert!(call("utf8", &[n(0xd800)]).is_err());
+    assert!(call("from_utf8", &[n(0xff)]).is_err());
+    assert!(call("from_utf8", &[s("")]).is_err());
+}
+
+#[test]
+fn test_rgb_builtins() {
+    let n = CompResult::Num;
+    assert_eq!(
+        call("rgb565", &[n(0xff), n(0x80), n(0)]).unwrap(),
+        n(0xfc00)
+    );
+    assert_eq!(call("rgb565", &[n(0xff8000)]).unwrap(), n(0xfc00));
+    assert!(call("rgb565", &[n(0x1ff8000)]).is_err());
+    assert_eq!(
+        call("unpack_rgb565", &[n(0xfc00)]).unwrap(),
+        CompResult::Tuple(vec![n(0xff), n(0x82), n(0)])
+    );
+    assert_eq!(
+        call("argb8888", &[n(0xff), n(0x80), n(0)]).unwrap(),
+        n(0xffff_8000)
+    );
+    assert_eq!(
+        call("argb8888", &[n(0x80), n(0xff), n(0x80), n(0)]).unwrap(),
+        n(0x80ff_8000)
+    );
+    assert!(call("argb8888", &[n(0x100), n(0), n(0), n(0)]).is_err());
+}
+
+#[test]
+fn test_wire_builtins() {
+    let n = CompResult::Num;
+    let b = |bytes: &[u8]| CompResult::Bytes(bytes.to_vec());
+    assert_eq!(call("varint", &[n(300)]).unwrap(), b(&[0xac, 0x02]));
+    assert_eq!(call("from_varint", &[n(0xac02)]).unwrap(), n(300));
+    assert_eq!(call("from_varint", &[b(&[0xac, 0x02])]).unwrap(), n(300));
+    assert_eq!(
+        call("uleb128", &[n(624_485)]).unwrap(),
+        b(&[0xe5, 0x8e, 0x26])
+    );
+    assert!(call("uleb128", &[n(-1)]).is_err());
+    assert_eq!(
+        call("sleb128", &[n(-123_456)]).unwrap(),
+        b(&[0xc0, 0xbb, 0x78])
+    );
+    assert_eq!(call("from_sleb128", &[n(0xc0bb78)]).unwrap(), n(-123_456));
+    assert!(call("from_uleb128", &[n(0x80)]).is_err());
+    assert_eq!(call("zigzag", &[n(-2)]).unwrap(), n(3));
+    assert_eq!(call("from_zigzag", &[n(3)]).unwrap(), n(-2));
+    assert!(call("zigzag", &[n(i128::MIN)]).is_err());
+    let minus_one = call("varint", &[n(-1)]).unwrap();
+    assert_eq!(call("from_varint", &[minus_one]).unwrap(), n(-1));
+    let top = CompResult::Bytes(wire::uleb128(1 << 127));
+    assert!(call("from_uleb128", &[top]).is_err());
+    assert_eq!(call("to_bcd", &[n(1234)]).unwrap(), n(0x1234));
+    assert_eq!(call("from_bcd", &[n(0x1234)]).unwrap(), n(1234));
+    assert!(call("from_bcd", &[n(0x1a)]).is_err());
+    assert_eq!(call("gray", &[n(5)]).unwrap(), n(7));
+    assert_eq!(call("from_gray", &[n(7)]).unwrap(), n(5));
+}
+
+#[test]
+fn test_sign_builtins() {
+    let n = CompResult::Num;
+    assert_eq!(call("from_ones", &[n(0xfa), n(8)]).unwrap(), n(-5));
+    assert_eq!(call("to_ones", &[n(-5), n(8)]).unwrap(), n(0xfa));
+    assert_eq!(call("from_sign_mag", &[n(0x805), n(12)]).unwrap(), n(-5));
+    assert_eq!(call("to_sign_mag", &[n(-5), n(12)]).unwrap(), n(0x805));
+    assert!(call("to_sign_mag", &[n(-2048), n(12)]).is_err());
+    assert_eq!(call("from_excess", &[n(0), n(8)]).unwrap(), n(-128));
+    assert_eq!(call("to_excess", &[n(-1), n(12)]).unwrap(), n(0x7ff));
+    assert_eq!(
+        call("from_excess", &[n(0x10), n(8), n(127)]).unwrap(),
+        n(-111)
+    );
+    assert_eq!(call("to_excess", &[n(-127), n(8), n(127)]).unwrap(), n(0));
+    assert!(call("from_ones", &[n(1), n(0)]).is_err());
+    assert!(call("from_ones", &[n(1)]).is_err());
+}
+
+#[test]
+fn test_text_encoding_builtins() {
+    let n = CompResult::Num;
+    let b = |text: &str| CompResult::Bytes(text.as_bytes().to_vec());
+    assert_eq!(call("b64enc", &[n(0x1234)]).unwrap(), b("EjQ="));
+    assert_eq!(call("b64enc", &[b("foobar")]).unwrap(), b("Zm9vYmFy"));
+    assert_eq!(call("b64dec", &[b("EjQ=")]).unwrap(), n(0x1234));
+    assert_eq!(call("b32enc", &[n(0x1234)]).unwrap(), b("CI2A===="));
+    assert_eq!(call("b32dec", &[b("CI2A====")]).unwrap(), n(0x1234));
+    assert_eq!(call("b58enc", &[n(0x1234)]).unwrap(), b("2PM"));
+    assert_eq!(call("b58dec", &[b("2PM")]).unwrap(), n(0x1234));
+    assert_eq!(call("hexenc", &[b("ab")]).unwrap(), b("6162"));
+    assert_eq!(call("hexdec", &[b("ff ff")]).unwrap(), n(0xffff));
+    let long = "00".repeat(17);
+    assert_eq!(
+        call("hexdec", &[b(&long)]).unwrap(),
+        CompResult::Bytes(vec![0; 17])
+    );
+    assert!(call("b64dec", &[b("E")]).is_err());
+    assert!(call("b64dec", &[n(1)]).is_err());
+}
@@ -0,0 +1,157 @@
+pub struct CrcParams {
+    pub width: u32,
+    pub poly: u64,
+    pub init: u64,
+    pub refin: bool,
+    pub refout: bool,
+    pub xorout: u64,
+}
+
+pub const CRC32: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x04c1_1db7,
+    init: 0xffff_ffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffff_ffff,
+};
+
+pub const CRC32C: CrcParams = CrcParams {
+    width: 32,
+    poly: 0x1edc_6f41,
+    init: 0xffff_ffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffff_ffff,
+};
+
+// CRC-16/CCITT-FALSE, the variant most tools mean by "CCITT"
+pub const CRC16_CCITT: CrcParams = CrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+pub const CRC8: CrcParams = CrcParams {
+    width: 8,
+    poly: 0x07,
+    init: 0,
+    refin: false,
+    refout: false,
+    xorout: 0,
+};
+
+fn reflect(val: u64, width: u32) -> u64 {
+    val.reverse_bits() >> (64 - width)
+}
+
+pub fn crc(data: &[u8], params: &CrcParams) -> u64 {
+    let mask = u64::MAX >> (64 - params.width);
+    let top = 1 << (params.width - 1);
+    let mut reg = params.init & mask;
+    for &byte in data {
+        let byte = if params.refin {
+            byte.reverse_bits()
+        } else {
+            byte
+        };
+        for i in (0..8).rev() {
+            let feedback = (reg & top != 0) != ((byte >> i) & 1 != 0);
+            reg = (reg << 1) & mask;
+            if feedback {
+                reg ^= params.poly & mask;
+            }
+        }
+    }
+    if params.refout {
+        reg = reflect(reg, params.width);
+    }
+    (reg ^ params.xorout) & mask
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + u32::from(byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+pub fn fletcher16(data: &[u8]) -> u16 {
+    let (a, b) = data.iter().fold((0u16, 0u16), |(a, b), &byte| {
+        let a = (a + u16::from(byte)) % 255;
+        (a, (b + a) % 255)
+    });
+    (b << 8) | a
+}
+
+pub fn fnv1a32(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+pub fn fnv1a64(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// RFC 1071 ones' complement sum of big-endian 16-bit words
+pub fn inet_csum(data: &[u8]) -> u16 {
+    let mut sum = data.chunks(2).fold(0u32, |sum, word| {
+        let word = (u32::from(word[0]) << 8) | word.get(1).copied().map_or(0, u32::from);
+        sum + word
+    });
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[test]
+fn test_crc() {
+    let check = b"123456789";
+    assert_eq!(crc(check, &CRC32), 0xcbf4_3926);
+    assert_eq!(crc(check, &CRC32C), 0xe306_9283);
+    assert_eq!(crc(check, &CRC16_CCITT), 0x29b1);
+    assert_eq!(crc(check, &CRC8), 0xf4);
+    let kermit = CrcParams {
+        width: 16,
+        poly: 0x1021,
+        init: 0,
+        refin: true,
+        refout: true,
+        xorout: 0,
+    };
+    assert_eq!(crc(check, &kermit), 0x2189);
+    let crc64_xz = CrcParams {
+        width: 64,
+        poly: 0x42f0_e1eb_a9ea_3693,
+        init: u64::MAX,
+        refin: true,
+        refout: true,
+        xorout: u64::MAX,
+    };
+    assert_eq!(crc(check, &crc64_xz), 0x995d_c9bb_df19_39fa);
+}
+
+#[test]
+fn test_checksums() {
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    assert_eq!(fletcher16(b"abcde"), 0xc8f0);
+    assert_eq!(fletcher16(b"abcdef"), 0x2057);
+    assert_eq!(fnv1a32(b""), 0x811c_9dc5);
+    assert_eq!(fnv1a32(b"a"), 0xe40c_292c);
+    assert_eq!(fnv1a64(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(
+        inet_csum(&[
+            0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0xc0, 0xa8,
+            0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7
+        ]),
+        0xb861
+    );
+}
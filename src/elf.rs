@@ -0,0 +1,247 @@
+use crate::error::{Error, Result};
+use std::convert::{TryFrom, TryInto};
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+const STT_SECTION: u8 = 3;
+const STT_FILE: u8 = 4;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub addr: u64,
+    pub size: u64,
+}
+
+// Symbols of the loaded binaries, sorted by address
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&self, off: u64) -> Option<[u8; N]> {
+        let off = usize::try_from(off).ok()?;
+        self.data.get(off..off.checked_add(N)?)?.try_into().ok()
+    }
+
+    fn u8(&self, off: u64) -> Option<u8> {
+        Some(self.bytes::<1>(off)?[0])
+    }
+
+    fn u16(&self, off: u64) -> Option<u16> {
+        let b = self.bytes(off)?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&self, off: u64) -> Option<u32> {
+        let b = self.bytes(off)?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&self, off: u64) -> Option<u64> {
+        let b = self.bytes(off)?;
+        Some(if self.big_endian {
+            u64::from_be_bytes(b)
+        } else {
+            u64::from_le_bytes(b)
+        })
+    }
+
+    // An address or size, which is 4 or 8 bytes depending on the class
+    fn word(&self, off: u64) -> Option<u64> {
+        if self.is_64 {
+            self.u64(off)
+        } else {
+            self.u32(off).map(u64::from)
+        }
+    }
+
+    fn str(&self, off: u64) -> Option<String> {
+        let start = usize::try_from(off).ok()?;
+        let len = self.data.get(start..)?.iter().position(|&b| b == 0)?;
+        String::from_utf8(self.data[start..start + len].to_vec()).ok()
+    }
+
+    fn symbols(&self) -> Option<Vec<Symbol>> {
+        let (shoff, shentsize, shnum) = if self.is_64 {
+            (self.u64(0x28)?, self.u16(0x3a)?, self.u16(0x3c)?)
+        } else {
+            (u64::from(self.u32(0x20)?), self.u16(0x2e)?, self.u16(0x30)?)
+        };
+        let len = self.data.len() as u64;
+        let section = |i: u64| {
+            Some(shoff.checked_add(i.checked_mul(u64::from(shentsize))?)?).filter(|&sh| sh < len)
+        };
+        // (type, offset, size, link, entsize) of a section header
+        let header = |i: u64| -> Option<(u32, u64, u64, u32, u64)> {
+            let sh = section(i)?;
+            Some(if self.is_64 {
+                (
+                    self.u32(sh + 4)?,
+                    self.u64(sh + 24)?,
+                    self.u64(sh + 32)?,
+                    self.u32(sh + 40)?,
+                    self.u64(sh + 56)?,
+                )
+            } else {
+                (
+                    self.u32(sh + 4)?,
+                    u64::from(self.u32(sh + 16)?),
+                    u64::from(self.u32(sh + 20)?),
+                    self.u32(sh + 24)?,
+                    u64::from(self.u32(sh + 36)?),
+                )
+            })
+        };
+        let mut symbols = Vec::new();
+        for i in 0..u64::from(shnum) {
+            let (ty, offset, size, link, entsize) = header(i)?;
+            if (ty != SHT_SYMTAB && ty != SHT_DYNSYM) || entsize == 0 {
+                continue;
+            }
+            let strtab = header(u64::from(link))?.1;
+            let end = offset.checked_add(size).filter(|&end| end <= len)?;
+            for sym in (offset..end).step_by(entsize as usize) {
+                let (name, info, addr, size) = if self.is_64 {
+                    (
+                        self.u32(sym)?,
+                        self.u8(sym + 4)?,
+                        self.u64(sym + 8)?,
+                        self.u64(sym + 16)?,
+                    )
+                } else {
+                    (
+                        self.u32(sym)?,
+                        self.u8(sym + 12)?,
+                        self.word(sym + 4)?,
+                        self.word(sym + 8)?,
+                    )
+                };
+                let name = self.str(strtab.checked_add(u64::from(name))?)?;
+                let kind = info & 0xf;
+                if !name.is_empty() && addr != 0 && kind != STT_SECTION && kind != STT_FILE {
+                    symbols.push(Symbol { name, addr, size });
+                }
+            }
+        }
+        Some(symbols)
+    }
+}
+
+impl SymbolTable {
+    // Reads the symbols of the .symtab and .dynsym sections of an ELF file
+    pub fn parse(data: &[u8]) -> Result<SymbolTable> {
+        let err = |s: &str| Error::ElfParseError(s.to_string());
+        if !data.starts_with(b"\x7fELF") || data.len() < 0x34 {
+            return Err(err("missing ELF header"));
+        }
+        let reader = Reader {
+            data,
+            is_64: match data[4] {
+                1 => false,
+                2 => true,
+                _ => return Err(err("unknown class")),
+            },
+            big_endian: match data[5] {
+                1 => false,
+                2 => true,
+                _ => return Err(err("unknown byte order")),
+            },
+        };
+        let mut table = SymbolTable::default();
+        table.extend(reader.symbols().ok_or_else(|| err("truncated file"))?);
+        Ok(table)
+    }
+
+    // Adds the symbols of another binary
+    pub fn merge(&mut self, other: SymbolTable) {
+        self.extend(other.symbols);
+    }
+
+    fn extend(&mut self, symbols: Vec<Symbol>) {
+        self.symbols.extend(symbols);
+        self.symbols
+            .sort_by(|a, b| a.addr.cmp(&b.addr).then(b.size.cmp(&a.size)));
+        self.symbols.dedup();
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|sym| sym.name == name)
+    }
+
+    // Names the symbol that `addr` lands in, like `func+0x1c/0x80`
+    pub fn annotate(&self, addr: u64) -> Option<String> {
+        let end = self.symbols.partition_point(|sym| sym.addr <= addr);
+        self.symbols[..end]
+            .iter()
+            .rev()
+            .find(|sym| addr - sym.addr < sym.size.max(1))
+            .map(|sym| format!("{}+{:#x}/{:#x}", sym.name, addr - sym.addr, sym.size))
+    }
+}
+
+#[test]
+fn test_elf64() {
+    let table = SymbolTable::parse(include_bytes!("../fixtures/symbols64.elf")).unwrap();
+    assert_eq!(
+        table.lookup("start_kernel"),
+        Some(&Symbol {
+            name: String::from("start_kernel"),
+            addr: 0x4000f2,
+            size: 0x11,
+        })
+    );
+    assert_eq!(table.lookup("counter").unwrap().addr, 0x401104);
+    assert_eq!(
+        table.annotate(0x4000f2 + 0x10).unwrap(),
+        "start_kernel+0x10/0x11"
+    );
+    assert_eq!(table.annotate(0x4000e8).unwrap(), "add+0x0/0xa");
+    assert_eq!(table.annotate(0x4000f2 + 0x11), None);
+    assert_eq!(table.annotate(0x1000), None);
+    assert_eq!(table.lookup("main"), None);
+}
+
+#[test]
+fn test_elf32() {
+    let table = SymbolTable::parse(include_bytes!("../fixtures/symbols32.elf")).unwrap();
+    assert_eq!(table.lookup("add").unwrap().addr, 0x8048094);
+    assert_eq!(
+        table.annotate(0x80480a5 + 3).unwrap(),
+        "start_kernel+0x3/0x11"
+    );
+    assert_eq!(table.annotate(0x80490b8).unwrap(), "counter+0x0/0x4");
+}
+
+#[test]
+fn test_elf_errors() {
+    assert!(
+        SymbolTable::parse(b"not an elf file at all, not even close to one..........").is_err()
+    );
+    let mut truncated = include_bytes!("../fixtures/symbols64.elf").to_vec();
+    truncated.truncate(0x100);
+    assert!(SymbolTable::parse(&truncated).is_err());
+}
@@ -0,0 +1,181 @@
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE32: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+// The Bitcoin alphabet, which leaves out 0, O, I and l
+const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Writes `bytes` `bits` at a time through `alphabet`, padding with `=` to a multiple of `block`
+fn encode_bits(bytes: &[u8], alphabet: &[u8], bits: u32, block: usize) -> String {
+    let mut s = String::new();
+    let (mut acc, mut len) = (0u32, 0);
+    for &b in bytes {
+        acc = acc << 8 | u32::from(b);
+        len += 8;
+        while len >= bits {
+            len -= bits;
+            s.push(alphabet[(acc >> len) as usize] as char);
+            acc &= (1 << len) - 1;
+        }
+    }
+    if len > 0 {
+        s.push(alphabet[(acc << (bits - len)) as usize] as char);
+    }
+    while !s.len().is_multiple_of(block) {
+        s.push('=');
+    }
+    s
+}
+
+// Reads the digits of `s`, ignoring whitespace and trailing padding. The bits left over must be
+// fewer than a digit and all zero
+fn decode_bits(s: &str, digit: fn(char) -> Option<u32>, bits: u32) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let (mut acc, mut len) = (0u32, 0);
+    let s = s.split_whitespace().collect::<String>();
+    for c in s.trim_end_matches('=').chars() {
+        acc = acc << bits | digit(c)?;
+        len += bits;
+        if len >= 8 {
+            len -= 8;
+            bytes.push((acc >> len) as u8);
+            acc &= (1 << len) - 1;
+        }
+    }
+    Some(bytes).filter(|_| len < bits && acc == 0)
+}
+
+fn position(alphabet: &[u8], c: char) -> Option<u32> {
+    alphabet
+        .iter()
+        .position(|&a| a as char == c)
+        .map(|i| i as u32)
+}
+
+pub fn base64(bytes: &[u8]) -> String {
+    encode_bits(bytes, BASE64, 6, 4)
+}
+
+// Takes the URL-safe alphabet as well, and padding may be left out
+pub fn from_base64(s: &str) -> Option<Vec<u8>> {
+    let digit = |c| match c {
+        '-' => Some(62),
+        '_' => Some(63),
+        c => position(BASE64, c),
+    };
+    decode_bits(s, digit, 6)
+}
+
+pub fn base32(bytes: &[u8]) -> String {
+    encode_bits(bytes, BASE32, 5, 8)
+}
+
+pub fn from_base32(s: &str) -> Option<Vec<u8>> {
+    decode_bits(s, |c| position(BASE32, c.to_ascii_uppercase()), 5)
+}
+
+// Base58 reads the bytes as one big-endian number, each leading zero byte becoming a `1`
+pub fn base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    // little-endian base-58 digits
+    let mut digits: Vec<u8> = Vec::new();
+    for &b in &bytes[zeros..] {
+        let mut carry = u32::from(b);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    "1".repeat(zeros)
+        + &digits
+            .iter()
+            .rev()
+            .map(|&d| BASE58[d as usize] as char)
+            .collect::<String>()
+}
+
+pub fn from_base58(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim();
+    let zeros = s.chars().take_while(|&c| c == '1').count();
+    // little-endian bytes
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in s[zeros..].chars() {
+        let mut carry = position(BASE58, c)?;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(std::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    Some(bytes)
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Whitespace between the digits is ignored
+pub fn from_hex(s: &str) -> Option<Vec<u8>> {
+    let digits = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16))
+        .collect::<Option<Vec<u32>>>()?;
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    Some(digits.chunks(2).map(|d| (d[0] << 4 | d[1]) as u8).collect())
+}
+
+#[test]
+fn test_base64() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64(&[0xfb, 0xff]), "+/8=");
+    assert_eq!(from_base64("Zm9vYg==").unwrap(), b"foob");
+    assert_eq!(from_base64("Zm9vYg").unwrap(), b"foob");
+    assert_eq!(from_base64("-_8=").unwrap(), vec![0xfb, 0xff]);
+    assert_eq!(from_base64("Zm9v\nYmFy").unwrap(), b"foobar");
+    assert_eq!(from_base64("Zh=="), None);
+    assert_eq!(from_base64("Zm9vY"), None);
+    assert_eq!(from_base64("Zm9v!"), None);
+}
+
+#[test]
+fn test_base32() {
+    assert_eq!(base32(b"f"), "MY======");
+    assert_eq!(base32(b"foob"), "MZXW6YQ=");
+    assert_eq!(base32(b"foobar"), "MZXW6YTBOI======");
+    assert_eq!(from_base32("MZXW6YTBOI======").unwrap(), b"foobar");
+    assert_eq!(from_base32("mzxw6yq").unwrap(), b"foob");
+    assert_eq!(from_base32("MZ1"), None);
+    assert_eq!(from_base32("M"), None);
+}
+
+#[test]
+fn test_base58_and_hex() {
+    assert_eq!(base58(b"hello world"), "StV1DL6CwTryKyV");
+    assert_eq!(base58(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd]), "11233QC4");
+    assert_eq!(base58(&[]), "");
+    assert_eq!(from_base58("StV1DL6CwTryKyV").unwrap(), b"hello world");
+    assert_eq!(
+        from_base58("11233QC4").unwrap(),
+        vec![0, 0, 0x28, 0x7f, 0xb4, 0xcd]
+    );
+    assert_eq!(from_base58("0OIl"), None);
+    assert_eq!(hex(&[0xde, 0xad, 0x01]), "dead01");
+    assert_eq!(from_hex("de ad 01").unwrap(), vec![0xde, 0xad, 0x01]);
+    assert_eq!(from_hex("dea"), None);
+    assert_eq!(from_hex("xx"), None);
+}
@@ -3,6 +3,7 @@ use std::fmt;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum Error {
     NumParseError(String),
@@ -10,6 +11,42 @@ pub enum Error {
     ExprParseError(Operator),
     ExprTermsError,
     UnmatchedParenthError,
+    StrParseError(String),
+    TypeParseError(String),
+    FnArgsError(String),
+    OptionParseError(String),
+    CommandParseError(String),
+    FormatParseError(String),
+    FormatRangeError(String),
+    LayoutParseError(String),
+    TableParseError(String),
+    FileReadError(String),
+    FileWriteError(String),
+    ElfParseError(String),
+}
+
+impl Error {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::NumParseError(_) => "num_parse",
+            Error::OperatorParseError(_) => "operator_parse",
+            Error::ExprParseError(_) => "expr_parse",
+            Error::ExprTermsError => "expr_terms",
+            Error::UnmatchedParenthError => "unmatched_parenth",
+            Error::StrParseError(_) => "str_parse",
+            Error::TypeParseError(_) => "type_parse",
+            Error::FnArgsError(_) => "fn_args",
+            Error::OptionParseError(_) => "option_parse",
+            Error::CommandParseError(_) => "command_parse",
+            Error::FormatParseError(_) => "format_parse",
+            Error::FormatRangeError(_) => "format_range",
+            Error::LayoutParseError(_) => "layout_parse",
+            Error::TableParseError(_) => "table_parse",
+            Error::FileReadError(_) => "file_read",
+            Error::FileWriteError(_) => "file_write",
+            Error::ElfParseError(_) => "elf_parse",
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -20,6 +57,18 @@ impl fmt::Display for Error {
             Error::ExprParseError(o) => write!(f, "Could not parse {} expression", o),
             Error::ExprTermsError => write!(f, "Incorrect terms found in expression"),
             Error::UnmatchedParenthError => write!(f, "Unmatched parenthesis in expression"),
+            Error::StrParseError(s) => write!(f, "Could not parse string {}", s),
+            Error::TypeParseError(s) => write!(f, "Unknown type {}", s),
+            Error::FnArgsError(s) => write!(f, "Invalid arguments for {}", s),
+            Error::OptionParseError(s) => write!(f, "Invalid option {}", s),
+            Error::CommandParseError(s) => write!(f, "Unknown command :{}", s),
+            Error::FormatParseError(s) => write!(f, "Could not parse format {}", s),
+            Error::FormatRangeError(s) => write!(f, "{} is out of range for the format", s),
+            Error::LayoutParseError(s) => write!(f, "Could not parse register layout {}", s),
+            Error::TableParseError(s) => write!(f, "Could not parse table {}", s),
+            Error::FileReadError(s) => write!(f, "Could not read file {}", s),
+            Error::FileWriteError(s) => write!(f, "Could not write file {}", s),
+            Error::ElfParseError(s) => write!(f, "Invalid ELF file, {}", s),
         }
     }
 }
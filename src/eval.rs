@@ -1,41 +1,191 @@
+use crate::builtins;
+use crate::elf::SymbolTable;
 use crate::error::Result;
+use crate::ioctl;
+use crate::layout::Layout;
 use crate::parser::{Operand, Operator, Parser};
+use crate::reprs::{show_bytes, IntType};
+use crate::tables::{self, Table};
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::rc::Rc;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum CompResult {
     Num(i128),
+    Typed(i128, IntType),
     Func(Vec<String>, Operand),
+    Builtin(&'static str),
+    Bytes(Vec<u8>),
+    Tuple(Vec<CompResult>),
+    Layout(Layout),
+    Decoded(i128, Layout),
+    Table(Table),
+    Named(i128, String),
 }
 
 impl Display for CompResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             CompResult::Num(i) => write!(f, "{}", i),
+            CompResult::Typed(i, ty) => write!(f, "{}", ty.fmt_dec(*i)),
             CompResult::Func(args, body) => write!(f, "|{}| {}", args.join(", "), body),
+            CompResult::Builtin(name) => write!(f, "<builtin {}>", name),
+            CompResult::Bytes(bytes) => write!(f, "{}", show_bytes(bytes)),
+            CompResult::Tuple(items) => write!(
+                f,
+                "({})",
+                items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            CompResult::Layout(layout) => write!(f, "{}", layout),
+            CompResult::Decoded(i, layout) => write!(
+                f,
+                "{}({:#x}) {{ {} }}",
+                layout.name,
+                i,
+                layout
+                    .fields
+                    .iter()
+                    .map(|field| format!("{}={}", field.name, field.extract(*i)))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            CompResult::Table(table) => write!(f, "{}", table),
+            CompResult::Named(_, names) => write!(f, "{}", names),
+        }
+    }
+}
+
+impl CompResult {
+    pub fn as_num(&self) -> Option<i128> {
+        match self {
+            CompResult::Num(i)
+            | CompResult::Typed(i, _)
+            | CompResult::Decoded(i, _)
+            | CompResult::Named(i, _) => Some(*i),
+            _ => None,
         }
     }
 }
 
-pub type Context = HashMap<String, CompResult>;
+// Variables, and the definitions such as register layouts and flag tables that stay visible
+// inside function calls
+#[derive(Debug, Default, Clone)]
+pub struct Context {
+    vars: HashMap<String, CompResult>,
+    defs: HashMap<String, CompResult>,
+    symbols: Rc<SymbolTable>,
+}
+
+impl Context {
+    pub fn new() -> Context {
+        Context::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<CompResult> {
+        self.vars
+            .get(name)
+            .or_else(|| self.defs.get(name))
+            .cloned()
+            .or_else(|| tables::builtin_table(name).map(CompResult::Table))
+            .or_else(|| (name == ioctl::NAME).then(|| CompResult::Layout(ioctl::layout())))
+            .or_else(|| tables::builtin_symbol(name).map(CompResult::Num))
+    }
+
+    pub fn insert(&mut self, name: String, value: CompResult) {
+        self.vars.insert(name, value);
+    }
+
+    pub fn load_symbols(&mut self, table: SymbolTable) {
+        Rc::make_mut(&mut self.symbols).merge(table);
+    }
+
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    // The variables sorted by name
+    pub fn vars(&self) -> Vec<(&str, &CompResult)> {
+        let mut vars = self
+            .vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+            .collect::<Vec<(&str, &CompResult)>>();
+        vars.sort_by_key(|(name, _)| *name);
+        vars
+    }
+
+    // Defines a layout or table, along with the symbols of a table
+    pub fn define(&mut self, value: CompResult) {
+        match &value {
+            CompResult::Layout(layout) => {
+                self.defs.insert(layout.name.clone(), value);
+            }
+            CompResult::Table(table) => {
+                for (symbol, v) in &table.entries {
+                    self.defs.insert(symbol.clone(), CompResult::Num(*v));
+                }
+                self.defs.insert(table.name.clone(), value);
+            }
+            _ => {}
+        }
+    }
+
+    // The layouts and tables, including the built-in ones, sorted by name
+    pub fn defs(&self) -> Vec<CompResult> {
+        let mut defs = tables::builtin_names()
+            .chain(Some(ioctl::NAME))
+            .filter(|name| !self.defs.contains_key(*name))
+            .filter_map(|name| self.get(name))
+            .chain(
+                self.defs
+                    .values()
+                    .filter(|def| !matches!(def, CompResult::Num(_)))
+                    .cloned(),
+            )
+            .collect::<Vec<CompResult>>();
+        defs.sort_by_key(|def| match def {
+            CompResult::Layout(layout) => layout.name.clone(),
+            CompResult::Table(table) => table.name.clone(),
+            _ => String::new(),
+        });
+        defs
+    }
+
+    // A context for a function call, with the definitions but none of the variables
+    fn scope(&self) -> Context {
+        Context {
+            vars: HashMap::new(),
+            defs: self.defs.clone(),
+            symbols: Rc::clone(&self.symbols),
+        }
+    }
+}
 
 pub fn eval_expr(expr: &str, ctx: &mut Context) -> Result<Option<CompResult>> {
     let oper: Result<Operand> = Parser::new(expr).into();
-    Ok(eval(oper?, ctx))
+    eval(oper?, ctx)
 }
 
-fn eval(term: Operand, ctx: &mut Context) -> Option<CompResult> {
-    match term {
+fn eval(term: Operand, ctx: &mut Context) -> Result<Option<CompResult>> {
+    Ok(match term {
         Operand::Num(i) => Some(CompResult::Num(i)),
+        Operand::Bytes(bytes) => Some(CompResult::Bytes(bytes)),
         Operand::Term(Operator::Assign, lhs, term) => {
             if let Operand::Var(var_name) = *lhs {
-                let x = eval(*term, ctx)?;
-                ctx.insert(var_name, x);
+                if let Some(x) = eval(*term, ctx)? {
+                    ctx.insert(var_name, x);
+                }
             }
             None
         }
-        Operand::Var(var_name) => ctx.get(&var_name).cloned(),
+        Operand::Var(var_name) => ctx
+            .get(&var_name)
+            .or_else(|| builtins::lookup(&var_name).map(CompResult::Builtin)),
         Operand::Term(Operator::FnBody, args, body) => {
             if let Operand::FnArgs(args) = *args {
                 Some(CompResult::Func(args, *body))
@@ -45,30 +195,56 @@ fn eval(term: Operand, ctx: &mut Context) -> Option<CompResult> {
         }
         Operand::Term(Operator::FnCall, method, actual_args) => {
             let method_decl = eval(*method, ctx)?;
-            if let CompResult::Func(formal_args, func_body) = method_decl {
-                let mut args: Vec<CompResult> = Vec::new();
-                let mut cur = Some(*actual_args);
-                while let Some(Operand::Term(Operator::List, left, right)) = cur {
-                    args.push(eval(*right, ctx).unwrap());
-                    cur = Some(*left);
+            let args = match eval_list(*actual_args, ctx)? {
+                Some(args) => args,
+                None => return Ok(None),
+            };
+            match method_decl {
+                Some(CompResult::Func(formal_args, func_body)) => {
+                    let mut ctx = ctx.scope();
+                    formal_args.iter().zip(args).for_each(|(formal, actual)| {
+                        ctx.insert(formal.clone(), actual);
+                    });
+                    eval(func_body, &mut ctx)?
                 }
-                if let Some(cur) = cur.take() {
-                    args.push(eval(cur, ctx).unwrap())
+                Some(CompResult::Builtin(name)) => {
+                    let args = args
+                        .into_iter()
+                        .map(|arg| match arg {
+                            CompResult::Typed(i, _) => CompResult::Num(i),
+                            other => other,
+                        })
+                        .collect::<Vec<CompResult>>();
+                    Some(builtins::call_with_ctx(name, &args, ctx)?)
                 }
-                let mut ctx = Context::new();
-                formal_args
-                    .iter()
-                    .zip(args.iter().rev())
-                    .for_each(|(actual, formal)| {
-                        ctx.insert(actual.clone(), formal.clone());
-                    });
-                eval(func_body, &mut ctx)
-            } else {
-                None
+                Some(CompResult::Tuple(items)) => match args[..] {
+                    [CompResult::Num(idx)] if idx >= 0 => items.get(idx as usize).cloned(),
+                    _ => None,
+                },
+                _ => None,
             }
         }
+        list @ Operand::Term(Operator::List, _, _) => eval_list(list, ctx)?.map(CompResult::Tuple),
+        Operand::Term(Operator::Cast(ty), _, term) => eval(*term, ctx)?
+            .and_then(|x| x.as_num())
+            .map(|x| CompResult::Typed(ty.wrap(x), ty)),
+        // numbers are compared by value whatever their type, other results as a whole
+        Operand::Term(op @ (Operator::Eq | Operator::Ne), l, r) => {
+            let l = eval(*l, ctx)?;
+            let r = eval(*r, ctx)?;
+            let equal = match (
+                l.as_ref().and_then(|l| l.as_num()),
+                r.as_ref().and_then(|r| r.as_num()),
+            ) {
+                (Some(l), Some(r)) => l == r,
+                _ => l.is_some() && l == r,
+            };
+            Some(CompResult::Num(i128::from(equal == (op == Operator::Eq))))
+        }
         Operand::Term(op, l, r) => {
-            if let (CompResult::Num(l), CompResult::Num(r)) = (eval(*l, ctx)?, eval(*r, ctx)?) {
+            let l = eval(*l, ctx)?.and_then(|l| l.as_num());
+            let r = eval(*r, ctx)?.and_then(|r| r.as_num());
+            if let (Some(l), Some(r)) = (l, r) {
                 Some(CompResult::Num(match op {
                     Operator::Add => l + r,
                     Operator::Mul => l * r,
@@ -90,13 +266,28 @@ fn eval(term: Operand, ctx: &mut Context) -> Option<CompResult> {
             }
         }
         other => panic!("Found unexpected operand: {:?}", other),
+    })
+}
+
+fn eval_list(term: Operand, ctx: &mut Context) -> Result<Option<Vec<CompResult>>> {
+    let mut items: Vec<Operand> = Vec::new();
+    let mut cur = Some(term);
+    while let Some(Operand::Term(Operator::List, left, right)) = cur {
+        items.push(*right);
+        cur = Some(*left);
     }
+    items.extend(cur.take());
+    items
+        .into_iter()
+        .rev()
+        .map(|item| eval(item, ctx))
+        .collect()
 }
 
 #[test]
 fn test_eval() {
     let test_eval = |expr| {
-        if let CompResult::Num(i) = eval_expr(expr, &mut HashMap::new()).unwrap().unwrap() {
+        if let CompResult::Num(i) = eval_expr(expr, &mut Context::new()).unwrap().unwrap() {
             i
         } else {
             panic!("Not a number!");
@@ -119,7 +310,7 @@ fn test_eval() {
 #[test]
 fn test_eval_unary() {
     let test_eval = |expr| {
-        if let CompResult::Num(i) = eval_expr(expr, &mut HashMap::new()).unwrap().unwrap() {
+        if let CompResult::Num(i) = eval_expr(expr, &mut Context::new()).unwrap().unwrap() {
             i
         } else {
             panic!("Not a number!");
@@ -135,7 +326,7 @@ fn test_eval_unary() {
 
 #[test]
 fn test_eval_ctx() {
-    let mut ctx = HashMap::new();
+    let mut ctx = Context::new();
     assert_eq!(eval_expr("$x = 1", &mut ctx).unwrap(), None);
     assert_eq!(eval_expr("$x", &mut ctx).unwrap(), Some(CompResult::Num(1)));
     assert_eq!(eval_expr("$y = ($x*3) << ($x+1)", &mut ctx).unwrap(), None);
@@ -148,7 +339,7 @@ fn test_eval_ctx() {
 
 #[test]
 fn test_eval_funcs() {
-    let mut ctx = HashMap::new();
+    let mut ctx = Context::new();
     assert_eq!(
         eval_expr("$f = |$x, $y, $z| ($x+$y)*$z", &mut ctx).unwrap(),
         None
@@ -172,3 +363,146 @@ fn test_eval_funcs() {
         Some(CompResult::Num(7))
     );
 }
+
+#[test]
+fn test_eval_builtins() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_expr("crc32(\"123456789\")", &mut ctx).unwrap(),
+        Some(CompResult::Num(0xcbf4_3926))
+    );
+    assert_eq!(
+        eval_expr("crc16_ccitt(\"123456789\") ^ 0x29b1", &mut ctx).unwrap(),
+        Some(CompResult::Num(0))
+    );
+    assert_eq!(eval_expr("$f = |$h, $x| $h($x)", &mut ctx).unwrap(), None);
+    assert_eq!(
+        eval_expr("$f(fnv1a32, \"a\")", &mut ctx).unwrap(),
+        Some(CompResult::Num(0xe40c_292c))
+    );
+    assert_eq!(
+        eval_expr("crc8", &mut ctx).unwrap(),
+        Some(CompResult::Builtin("crc8"))
+    );
+    assert_eq!(eval_expr("$d = b64dec", &mut ctx).unwrap(), None);
+    assert_eq!(
+        eval_expr("$d(\"AQI=\") + $f(b32dec, \"AEBA\")", &mut ctx).unwrap(),
+        Some(CompResult::Num(0x0204))
+    );
+    assert!(eval_expr("crc32(1, 2)", &mut ctx).is_err());
+}
+
+#[test]
+fn test_eval_tuples() {
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_expr("$t = deinterleave(interleave(3, 4))", &mut ctx).unwrap(),
+        None
+    );
+    assert_eq!(
+        eval_expr("$t", &mut ctx).unwrap().unwrap().to_string(),
+        "(3, 4)"
+    );
+    assert_eq!(
+        eval_expr("$t(1) * 2", &mut ctx).unwrap(),
+        Some(CompResult::Num(8))
+    );
+    assert_eq!(
+        eval_expr("permute(0b01, (1, 0))", &mut ctx).unwrap(),
+        Some(CompResult::Num(0b10))
+    );
+    assert_eq!(eval_expr("$t(2)", &mut ctx).unwrap(), None);
+}
+
+#[test]
+fn test_eval_decode() {
+    let mut ctx = Context::new();
+    let layout = Layout::parse("CTRL { EN[0], MODE[3:1] }").unwrap();
+    ctx.define(CompResult::Layout(layout.clone()));
+    assert_eq!(
+        eval_expr("decode(CTRL, 0xb)", &mut ctx).unwrap(),
+        Some(CompResult::Decoded(0xb, layout))
+    );
+    assert_eq!(
+        eval_expr("decode(CTRL, 0xb)", &mut ctx)
+            .unwrap()
+            .unwrap()
+            .to_string(),
+        "CTRL(0xb) { MODE=5, EN=1 }"
+    );
+    assert_eq!(
+        eval_expr("decode(CTRL, 3 as u8) + 1", &mut ctx).unwrap(),
+        Some(CompResult::Num(4))
+    );
+    assert!(eval_expr("decode(1, 2)", &mut ctx).is_err());
+}
+
+#[test]
+fn test_eval_tables() {
+    use crate::tables::TableKind;
+    let mut ctx = Context::new();
+    assert_eq!(
+        eval_expr("O_WRONLY | O_CREAT", &mut ctx).unwrap(),
+        Some(CompResult::Num(0o101))
+    );
+    assert_eq!(
+        eval_expr("decode(OPEN, 0x41 | 0x80000000)", &mut ctx)
+            .unwrap()
+            .unwrap()
+            .to_string(),
+        "O_WRONLY|O_CREAT|0x80000000 (unknown)"
+    );
+    let table = Table::parse(TableKind::Enum, "STATE { IDLE=0, RUN=1 }").unwrap();
+    ctx.define(CompResult::Table(table));
+    assert_eq!(
+        eval_expr("$f = |$x| decode(STATE, $x + RUN)", &mut ctx).unwrap(),
+        None
+    );
+    assert_eq!(
+        eval_expr("$f(0)", &mut ctx).unwrap(),
+        Some(CompResult::Named(1, String::from("RUN")))
+    );
+    assert_eq!(eval_expr("$x = 1", &mut ctx).unwrap(), None);
+    assert_eq!(eval_expr("(|$y| $x)(0)", &mut ctx).unwrap(), None);
+    let defs = ctx.defs();
+    assert_eq!(defs.len(), 6);
+    assert!(matches!(&defs[0], CompResult::Layout(layout) if layout.name == "IOCTL"));
+    assert!(matches!(&defs[5], CompResult::Table(table) if table.name == "STATE"));
+}
+
+#[test]
+fn test_eval_symbols() {
+    let mut ctx = Context::new();
+    assert!(eval_expr("sym(\"start_kernel\")", &mut ctx).is_err());
+    let table = SymbolTable::parse(include_bytes!("../fixtures/symbols64.elf")).unwrap();
+    ctx.load_symbols(table);
+    assert_eq!(
+        eval_expr("(|$s| sym($s) + 0x10)(\"start_kernel\")", &mut ctx).unwrap(),
+        Some(CompResult::Num(0x400102))
+    );
+    assert!(eval_expr("sym(\"main\")", &mut ctx).is_err());
+    assert!(eval_expr("sym(1)", &mut ctx).is_err());
+}
+
+#[test]
+fn test_eval_compare() {
+    let mut ctx = Context::new();
+    let mut eval = |expr| eval_expr(expr, &mut ctx).unwrap();
+    assert_eq!(eval("1 + 1 == 2"), Some(CompResult::Num(1)));
+    assert_eq!(eval("3 & 1 != 1"), Some(CompResult::Num(0)));
+    assert_eq!(eval("-1 as u8 == 255"), Some(CompResult::Num(1)));
+    assert_eq!(eval("$net = 10.1.0.0/16"), None);
+    assert_eq!(
+        eval("(10.1.2.3 & $net(1)) == $net(0)"),
+        Some(CompResult::Num(1))
+    );
+    assert_eq!(
+        eval("(10.2.0.1 & $net(1)) == $net(0)"),
+        Some(CompResult::Num(0))
+    );
+    assert_eq!(
+        eval("$net == (10.1.0.0, 255.255.0.0)"),
+        Some(CompResult::Num(1))
+    );
+    assert_eq!(eval("\"ab\" != \"ab\""), Some(CompResult::Num(0)));
+}
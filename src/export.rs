@@ -0,0 +1,339 @@
+use crate::error::{Error, Result};
+use crate::eval::{CompResult, Context};
+use crate::parser::{Operand, Operator};
+use crate::reprs::{show_bytes, IntType};
+use crate::template::Radix;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Lang {
+    C,
+    Rust,
+    Python,
+}
+
+impl Lang {
+    pub fn parse(name: &str) -> Result<Lang> {
+        match name {
+            "c" => Ok(Lang::C),
+            "rust" => Ok(Lang::Rust),
+            "python" => Ok(Lang::Python),
+            _ => Err(Error::OptionParseError(name.to_string())),
+        }
+    }
+
+    fn comment(self) -> &'static str {
+        match self {
+            Lang::C | Lang::Rust => "//",
+            Lang::Python => "#",
+        }
+    }
+}
+
+fn standard(ty: IntType) -> bool {
+    [8, 16, 32, 64, 128].contains(&ty.bits)
+}
+
+// A value as its sign and magnitude, which holds any `u128` as well as any `i128`
+type Value = (bool, u128);
+
+fn signed(x: i128) -> Value {
+    (x < 0, x.unsigned_abs())
+}
+
+// Reads `x` at the width of `ty`, unsigned values above `i128::MAX` included
+fn typed(x: i128, ty: IntType) -> Value {
+    if ty.signed {
+        signed(ty.wrap(x))
+    } else {
+        (false, ty.raw(x))
+    }
+}
+
+// C has no literals for integers wider than `long long` and `unsigned long long`
+fn fits_c((negative, abs): Value) -> bool {
+    if negative {
+        abs <= 1 << 63
+    } else {
+        abs <= u128::from(u64::MAX)
+    }
+}
+
+fn digits(abs: u128, radix: Radix, lang: Lang) -> String {
+    match radix {
+        Radix::Dec => abs.to_string(),
+        Radix::Hex => format!("0x{:x}", abs),
+        Radix::HexUpper => format!("0x{:X}", abs),
+        Radix::Bin => format!("0b{:b}", abs),
+        Radix::Oct if lang == Lang::C && abs != 0 => format!("0{:o}", abs),
+        Radix::Oct if lang == Lang::C => String::from("0"),
+        Radix::Oct => format!("0o{:o}", abs),
+    }
+}
+
+fn literal((negative, abs): Value, radix: Radix, lang: Lang) -> String {
+    // the magnitude of `i64::MIN` would be an `unsigned long long` in C, making it positive
+    if lang == Lang::C && negative && abs == 1 << 63 {
+        return format!("(-{}LL - 1)", digits(abs - 1, radix, lang));
+    }
+    let digits = digits(abs, radix, lang);
+    let int_max = u128::from(i32::MAX as u32);
+    let suffix = if lang != Lang::C || abs <= int_max || (negative && abs == int_max + 1) {
+        ""
+    } else if negative {
+        "LL"
+    } else {
+        "ULL"
+    };
+    match (negative, lang) {
+        (true, Lang::C) => format!("(-{}{})", digits, suffix),
+        (true, _) => format!("-{}{}", digits, suffix),
+        (false, _) => format!("{}{}", digits, suffix),
+    }
+}
+
+// Renders a function body, or None if it uses something the language can't express
+fn body(term: &Operand, args: &[String], radix: Radix, lang: Lang) -> Option<String> {
+    let render = |term: &Operand| body(term, args, radix, lang);
+    Some(match term {
+        Operand::Num(i) if lang == Lang::C && !fits_c(signed(*i)) => return None,
+        Operand::Num(i) => literal(signed(*i), radix, lang),
+        Operand::Var(name) if args.contains(name) => {
+            let name = name.trim_start_matches('$');
+            match lang {
+                Lang::C => format!("({})", name),
+                _ => name.to_string(),
+            }
+        }
+        Operand::Term(Operator::Neg, _, r) => format!("(-{})", render(r)?),
+        Operand::Term(Operator::BNot, _, r) if lang == Lang::Rust => format!("(!{})", render(r)?),
+        Operand::Term(Operator::BNot, _, r) => format!("(~{})", render(r)?),
+        Operand::Term(Operator::Pow, l, r) => match lang {
+            Lang::C => return None,
+            Lang::Rust => format!("i128::pow({}, {} as u32)", render(l)?, render(r)?),
+            Lang::Python => format!("({} ** {})", render(l)?, render(r)?),
+        },
+        Operand::Term(Operator::Cast(ty), _, r) if standard(*ty) => match lang {
+            Lang::C if ty.bits > 64 => return None,
+            Lang::C => format!(
+                "(({}int{}_t){})",
+                if ty.signed { "" } else { "u" },
+                ty.bits,
+                render(r)?
+            ),
+            Lang::Rust => format!("(({} as {}) as i128)", render(r)?, ty),
+            Lang::Python => return None,
+        },
+        // Python's `//` and `%` round toward negative infinity, while xc truncates toward zero
+        Operand::Term(op @ (Operator::Div | Operator::Remainder), l, r) if lang == Lang::Python => {
+            let (l, r) = (render(l)?, render(r)?);
+            let same_sign = format!("({} < 0) == ({} < 0)", l, r);
+            if *op == Operator::Div {
+                format!("({} // {} if {} else -(-{} // {}))", l, r, same_sign, l, r)
+            } else {
+                format!("({} % {} if {} else {} % -{})", l, r, same_sign, l, r)
+            }
+        }
+        Operand::Term(op, l, r) => {
+            let op = match op {
+                Operator::Add
+                | Operator::Sub
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Remainder
+                | Operator::BXor
+                | Operator::BOr
+                | Operator::BAnd
+                | Operator::LShift
+                | Operator::RShift => op.to_string(),
+                _ => return None,
+            };
+            format!("({} {} {})", render(l)?, op, render(r)?)
+        }
+        _ => return None,
+    })
+}
+
+fn definition(name: &str, value: &CompResult, radix: Radix, lang: Lang) -> Option<String> {
+    let num = |x: Value, ty: IntType| match lang {
+        Lang::C if !fits_c(x) => None,
+        Lang::C => Some(format!("#define {} {}", name, literal(x, radix, lang))),
+        Lang::Rust => Some(format!(
+            "pub const {}: {} = {};",
+            name,
+            ty,
+            literal(x, radix, lang)
+        )),
+        Lang::Python => Some(format!("{} = {}", name, literal(x, radix, lang))),
+    };
+    Some(match value {
+        CompResult::Typed(x, ty) if standard(*ty) => num(typed(*x, *ty), *ty)?,
+        CompResult::Typed(x, ty) => num(typed(*x, *ty), IntType::fitting(ty.wrap(*x)))?,
+        CompResult::Func(args, term) => {
+            let body = body(term, args, radix, lang)?;
+            let args = args
+                .iter()
+                .map(|arg| arg.trim_start_matches('$'))
+                .collect::<Vec<&str>>();
+            match lang {
+                Lang::C => format!("#define {}({}) {}", name, args.join(", "), body),
+                Lang::Rust => format!(
+                    "pub fn {}({}) -> i128 {{\n    {}\n}}",
+                    name,
+                    args.iter()
+                        .map(|arg| format!("{}: i128", arg))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    body
+                ),
+                Lang::Python => format!("{} = lambda {}: {}", name, args.join(", "), body),
+            }
+        }
+        CompResult::Bytes(bytes) => match lang {
+            Lang::C => format!("#define {} {}", name, show_bytes(bytes)),
+            Lang::Rust => format!("pub const {}: &[u8] = b{};", name, show_bytes(bytes)),
+            Lang::Python => format!("{} = b{}", name, show_bytes(bytes)),
+        },
+        CompResult::Tuple(items) if lang == Lang::Python => format!(
+            "{} = ({}{})",
+            name,
+            items
+                .iter()
+                .map(|item| item.as_num().map(|x| literal(signed(x), radix, lang)))
+                .collect::<Option<Vec<String>>>()?
+                .join(", "),
+            if items.len() == 1 { "," } else { "" }
+        ),
+        value => {
+            let x = value.as_num()?;
+            num(signed(x), IntType::fitting(x))?
+        }
+    })
+}
+
+// Writes the variables as constant definitions, noting the ones that can't be written
+pub fn export(ctx: &Context, lang: Lang, radix: Radix) -> String {
+    ctx.vars()
+        .into_iter()
+        .map(|(name, value)| {
+            let name = name.trim_start_matches('$');
+            definition(name, value, radix, lang)
+                .unwrap_or_else(|| format!("{} skipped {}", lang.comment(), name))
+        })
+        .map(|def| def + "\n")
+        .collect()
+}
+
+#[test]
+fn test_export() {
+    use crate::eval::eval_expr;
+    let mut ctx = Context::new();
+    for expr in [
+        "$MASK = 0xf0",
+        "$neg = -2 as i8",
+        "$odd = 5 as u12",
+        "$big = 1 << 40",
+        "$s = \"a\\n\"",
+        "$t = (1, 2)",
+        "$f = |$x, $y| ($x + $y) * 2",
+        "$g = |$x| ~$x ** 2",
+        "$h = crc32",
+    ]
+    .iter()
+    {
+        eval_expr(expr, &mut ctx).unwrap();
+    }
+    assert_eq!(
+        export(&ctx, Lang::C, Radix::Hex),
+        "#define MASK 0xf0\n\
+         #define big 0x10000000000ULL\n\
+         #define f(x, y) (((x) + (y)) * 0x2)\n\
+         // skipped g\n\
+         // skipped h\n\
+         #define neg (-0x2)\n\
+         #define odd 0x5\n\
+         #define s \"a\\n\"\n\
+         // skipped t\n"
+    );
+    assert_eq!(
+        export(&ctx, Lang::Rust, Radix::Dec),
+        "pub const MASK: u8 = 240;\n\
+         pub const big: u64 = 1099511627776;\n\
+         pub fn f(x: i128, y: i128) -> i128 {\n    ((x + y) * 2)\n}\n\
+         pub fn g(x: i128) -> i128 {\n    i128::pow((!x), 2 as u32)\n}\n\
+         // skipped h\n\
+         pub const neg: i8 = -2;\n\
+         pub const odd: u8 = 5;\n\
+         pub const s: &[u8] = b\"a\\n\";\n\
+         // skipped t\n"
+    );
+    assert_eq!(
+        export(&ctx, Lang::Python, Radix::Bin),
+        "MASK = 0b11110000\n\
+         big = 0b10000000000000000000000000000000000000000\n\
+         f = lambda x, y: ((x + y) * 0b10)\n\
+         g = lambda x: ((~x) ** 0b10)\n\
+         # skipped h\n\
+         neg = -0b10\n\
+         odd = 0b101\n\
+         s = b\"a\\n\"\n\
+         t = (0b1, 0b10)\n"
+    );
+    let mut ctx = Context::new();
+    eval_expr("$d = |$x, $y| $x / $y - $x % $y", &mut ctx).unwrap();
+    assert_eq!(
+        eval_expr("$d(-7, 2)", &mut ctx).unwrap(),
+        Some(CompResult::Num(-2))
+    );
+    assert_eq!(
+        export(&ctx, Lang::Python, Radix::Dec),
+        "d = lambda x, y: ((x // y if (x < 0) == (y < 0) else -(-x // y)) - \
+         (x % y if (x < 0) == (y < 0) else x % -y))\n"
+    );
+    let mut ctx = Context::new();
+    for expr in [
+        "$wide = 1 << 64",
+        "$max = 0xffffffffffffffff",
+        "$min = -(1 << 63)",
+        "$low = -(1 << 63) - 1",
+        "$k = |$x| $x + 0x10000000000000000",
+    ]
+    .iter()
+    {
+        eval_expr(expr, &mut ctx).unwrap();
+    }
+    assert_eq!(
+        export(&ctx, Lang::C, Radix::Hex),
+        "// skipped k\n\
+         // skipped low\n\
+         #define max 0xffffffffffffffffULL\n\
+         #define min (-0x7fffffffffffffffLL - 1)\n\
+         // skipped wide\n"
+    );
+    assert_eq!(literal(signed(0o755), Radix::Oct, Lang::C), "0755");
+    assert_eq!(
+        literal(signed(-0x1_0000_0000), Radix::HexUpper, Lang::C),
+        "(-0x100000000LL)"
+    );
+    assert_eq!(
+        literal(signed(i64::MIN.into()), Radix::Dec, Lang::C),
+        "(-9223372036854775807LL - 1)"
+    );
+    let mut ctx = Context::new();
+    for expr in ["$u = -1 as u128", "$w = |$x| $x as u128"].iter() {
+        eval_expr(expr, &mut ctx).unwrap();
+    }
+    assert_eq!(
+        export(&ctx, Lang::C, Radix::Hex),
+        "// skipped u\n// skipped w\n"
+    );
+    assert_eq!(
+        export(&ctx, Lang::Rust, Radix::Hex),
+        "pub const u: u128 = 0xffffffffffffffffffffffffffffffff;\n\
+         pub fn w(x: i128) -> i128 {\n    ((x as u128) as i128)\n}\n"
+    );
+    assert_eq!(
+        export(&ctx, Lang::Python, Radix::Hex),
+        "u = 0xffffffffffffffffffffffffffffffff\n# skipped w\n"
+    );
+    assert!(Lang::parse("go").is_err());
+}
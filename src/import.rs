@@ -0,0 +1,252 @@
+use crate::eval::{eval_expr, Context};
+use crate::reprs::IntType;
+
+// Removes `/* */` and `//` comments, keeping line breaks
+fn strip_comments(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("/*") {
+            let end = after.find("*/").map_or(after.len(), |i| i + 2);
+            out.extend(after[..end].chars().filter(|&c| c == '\n'));
+            rest = &after[end..];
+        } else if rest.starts_with("//") {
+            rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+        } else {
+            let c = rest.chars().next().unwrap();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    out
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// Finds `#define NAME value`, `enum { A = 1, B }` and `const NAME: T = value;` definitions,
+// returning each name with the text of its value
+pub fn scan(text: &str) -> Vec<(String, String)> {
+    let text = strip_comments(text).replace("\\\n", " ");
+    let mut defs = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if let Some(define) = line.strip_prefix('#').map(str::trim_start) {
+            if let Some(define) = define.strip_prefix("define") {
+                let define = define.trim();
+                let name_end = define
+                    .find(|c: char| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(define.len());
+                let (name, value) = define.split_at(name_end);
+                // function-like macros are skipped
+                if is_ident(name) && !value.starts_with('(') && !value.trim().is_empty() {
+                    defs.push((name.to_string(), value.trim().to_string()));
+                }
+            }
+        } else if let Some(decl) = line
+            .split_whitespace()
+            .skip_while(|word| *word == "pub" || word.starts_with("pub("))
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .strip_prefix("const ")
+        {
+            let colon = decl.find(':');
+            let eq = decl.find('=');
+            let semi = decl.rfind(';');
+            if let (Some(colon), Some(eq), Some(semi)) = (colon, eq, semi) {
+                let name = decl[..colon].trim();
+                if is_ident(name) && colon < eq && eq < semi {
+                    defs.push((name.to_string(), decl[eq + 1..semi].trim().to_string()));
+                }
+            }
+        } else if line.starts_with("enum") || line.starts_with("typedef enum") {
+            let mut body = line.to_string();
+            while !body.contains('}') {
+                match lines.next() {
+                    Some(next) => body.push_str(next),
+                    None => break,
+                }
+            }
+            let (open, close) = match (body.find('{'), body.find('}')) {
+                (Some(open), Some(close)) if open < close => (open, close),
+                _ => continue,
+            };
+            let mut prev: Option<String> = None;
+            for variant in body[open + 1..close].split(',') {
+                let (name, value) = match variant.find('=') {
+                    Some(eq) => (variant[..eq].trim(), variant[eq + 1..].trim().to_string()),
+                    None => (
+                        variant.trim(),
+                        prev.as_ref()
+                            .map_or_else(|| String::from("0"), |prev| format!("{} + 1", prev)),
+                    ),
+                };
+                if is_ident(name) {
+                    defs.push((name.to_string(), value));
+                    prev = Some(name.to_string());
+                }
+            }
+        }
+    }
+    defs
+}
+
+// Rewrites a C or Rust value for the parser, dropping integer suffixes like `UL` and `_u32`,
+// giving C octal literals like `0755` the `0o` prefix and turning names that are already defined
+// into `$NAME` variables. Other names would read as hex, so the value is left out
+fn translate(value: &str, ctx: &Context) -> Option<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(c) = rest.chars().next() {
+        if c == '"' {
+            let end = rest[1..].find('"').map_or(rest.len(), |i| i + 2);
+            out.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            if c.is_ascii_digit() {
+                let mut num = word.replace('_', "");
+                for suffix in ["size", "128", "64", "32", "16", "8"].iter() {
+                    let len = num.len();
+                    if num.ends_with(suffix) && len > suffix.len() + 1 {
+                        let sign = &num[len - suffix.len() - 1..len - suffix.len()];
+                        if sign == "u" || sign == "i" {
+                            num.truncate(len - suffix.len() - 1);
+                            break;
+                        }
+                    }
+                }
+                let num = num.trim_end_matches(['u', 'U', 'l', 'L']);
+                match num.strip_prefix('0') {
+                    Some(oct) if !oct.is_empty() && oct.chars().all(|c| c.is_ascii_digit()) => {
+                        if !oct.chars().all(|c| c.is_digit(8)) {
+                            return None;
+                        }
+                        out.push_str("0o");
+                        out.push_str(oct);
+                    }
+                    _ => out.push_str(num),
+                }
+            } else if ctx.get(&format!("${}", word)).is_some() {
+                out.push('$');
+                out.push_str(word);
+            } else if word == "as" || IntType::parse(word).is_some() {
+                out.push_str(word);
+            } else {
+                return None;
+            }
+            rest = &rest[end..];
+        } else {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Some(out)
+}
+
+// Evaluates each definition found in `text` and stores it as `$NAME`, returning the names that
+// were imported and those that couldn't be evaluated
+pub fn import(text: &str, ctx: &mut Context) -> (Vec<String>, Vec<String>) {
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, value) in scan(text) {
+        let res = translate(&value, ctx).and_then(|expr| eval_expr(&expr, ctx).ok());
+        match res {
+            Some(Some(res)) => {
+                ctx.insert(format!("${}", name), res);
+                imported.push(name);
+            }
+            _ => skipped.push(name),
+        }
+    }
+    (imported, skipped)
+}
+
+#[test]
+fn test_scan() {
+    let defs = scan(
+        "#ifndef REGS_H\n\
+         #define REGS_H\n\
+         #define IRQ_SHIFT 4 /* irq bits */\n\
+         #  define IRQ_MASK (0xfUL << IRQ_SHIFT) // mask\n\
+         #define MAX(a, b) ((a) > (b) ? (a) : (b))\n\
+         #define LONG \\\n    0x100\n\
+         typedef enum {\n    MODE_OFF,\n    MODE_ON = 5,\n    MODE_AUTO, /* next */\n} mode_t;\n\
+         pub const PAGE_SIZE: usize = 4096;\n\
+         pub(crate) const FLAGS: u32 = 0x1_0000_u32;\n\
+         const fn f() -> u32 { 1 }\n",
+    );
+    let defs = defs
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<String>>();
+    assert_eq!(
+        defs,
+        vec![
+            "IRQ_SHIFT=4",
+            "IRQ_MASK=(0xfUL << IRQ_SHIFT)",
+            "LONG=0x100",
+            "MODE_OFF=0",
+            "MODE_ON=5",
+            "MODE_AUTO=MODE_ON + 1",
+            "PAGE_SIZE=4096",
+            "FLAGS=0x1_0000_u32",
+        ]
+    );
+}
+
+#[test]
+fn test_import() {
+    use crate::eval::CompResult;
+    let mut ctx = Context::new();
+    let (imported, skipped) = import(
+        "#define IRQ_SHIFT 4\n\
+         #define IRQ_MASK (0xfUL << IRQ_SHIFT)\n\
+         #define NAME \"uart0\"\n\
+         #define PI 3.14\n\
+         enum { A_ONE = 1, A_TWO };\n\
+         const SIZE: u64 = 1_000u64 * 2;\n",
+        &mut ctx,
+    );
+    assert_eq!(
+        imported,
+        vec!["IRQ_SHIFT", "IRQ_MASK", "NAME", "A_ONE", "A_TWO", "SIZE"]
+    );
+    assert_eq!(skipped, vec!["PI"]);
+    assert_eq!(ctx.get("$IRQ_MASK"), Some(CompResult::Num(0xf0)));
+    assert_eq!(ctx.get("$A_TWO"), Some(CompResult::Num(2)));
+    assert_eq!(ctx.get("$SIZE"), Some(CompResult::Num(2000)));
+    assert_eq!(
+        eval_expr("$IRQ_MASK & 0x1234", &mut ctx).unwrap(),
+        Some(CompResult::Num(0x30))
+    );
+}
+
+#[test]
+fn test_import_octal_and_undefined() {
+    use crate::eval::CompResult;
+    let mut ctx = Context::new();
+    let (imported, skipped) = import(
+        "#define MODE 0755
+         #define ZERO 0
+         #define DEC 0x0755
+         #define ADDR BAD + 1
+         #define NEXT ZERO + 1
+         #define BAD_OCT 089
+",
+        &mut ctx,
+    );
+    assert_eq!(imported, vec!["MODE", "ZERO", "DEC", "NEXT"]);
+    assert_eq!(skipped, vec!["ADDR", "BAD_OCT"]);
+    assert_eq!(ctx.get("$MODE"), Some(CompResult::Num(0o755)));
+    assert_eq!(ctx.get("$ZERO"), Some(CompResult::Num(0)));
+    assert_eq!(ctx.get("$DEC"), Some(CompResult::Num(0x755)));
+    assert_eq!(ctx.get("$NEXT"), Some(CompResult::Num(1)));
+}
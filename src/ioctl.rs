@@ -0,0 +1,56 @@
+use crate::layout::Layout;
+use std::convert::TryFrom;
+
+// The generic Linux encoding of ioctl request numbers, as in asm-generic/ioctl.h
+pub const NAME: &str = "IOCTL";
+
+pub const NONE: u32 = 0;
+pub const WRITE: u32 = 1;
+pub const READ: u32 = 2;
+
+const MACROS: [&str; 4] = ["_IO", "_IOW", "_IOR", "_IOWR"];
+
+pub fn layout() -> Layout {
+    Layout::parse("IOCTL { DIR[31:30], SIZE[29:16], TYPE[15:8], NR[7:0] }").unwrap()
+}
+
+// Builds a request number, or None if a part doesn't fit its field
+pub fn encode(dir: u32, ty: i128, nr: i128, size: i128) -> Option<i128> {
+    let ty = u8::try_from(ty).ok()?;
+    let nr = u8::try_from(nr).ok()?;
+    let size = u16::try_from(size).ok().filter(|&size| size < 1 << 14)?;
+    Some(i128::from(dir) << 30 | i128::from(size) << 16 | i128::from(ty) << 8 | i128::from(nr))
+}
+
+// Writes a request number back as the macro that builds it, like `_IOR('T', 0x13, 8)`
+pub fn describe(x: i128) -> Option<String> {
+    let x = u32::try_from(x).ok()?;
+    let (dir, size, ty, nr) = (x >> 30, (x >> 16) & 0x3fff, (x >> 8) & 0xff, x & 0xff);
+    let ty = match char::from(ty as u8) {
+        c if c.is_ascii_graphic() => format!("'{}'", c),
+        _ => format!("{:#x}", ty),
+    };
+    Some(if dir == NONE && size == 0 {
+        format!("_IO({}, {:#x})", ty, nr)
+    } else {
+        format!("{}({}, {:#x}, {})", MACROS[dir as usize], ty, nr, size)
+    })
+}
+
+#[test]
+fn test_ioctl() {
+    // TCGETS2 and TIOCGWINSZ on x86-64
+    assert_eq!(encode(READ, 0x54, 0x2a, 44), Some(0x802c_542a));
+    assert_eq!(encode(NONE, 0x54, 0x13, 0), Some(0x5413));
+    assert_eq!(encode(READ | WRITE, 0x41, 0x11, 0x1000), Some(0xd000_4111));
+    assert_eq!(encode(READ, 0x100, 0, 0), None);
+    assert_eq!(encode(READ, 0, 0, 1 << 14), None);
+    assert_eq!(describe(0x802c_542a).unwrap(), "_IOR('T', 0x2a, 44)");
+    assert_eq!(describe(0x5413).unwrap(), "_IO('T', 0x13)");
+    assert_eq!(describe(0x4008_0a01).unwrap(), "_IOW(0xa, 0x1, 8)");
+    assert_eq!(describe(0xc000_4111).unwrap(), "_IOWR('A', 0x11, 0)");
+    assert_eq!(describe(1 << 32), None);
+    let fields = layout().fields;
+    assert_eq!(fields[0].extract(0x802c_542a), READ.into());
+    assert_eq!(fields[1].extract(0x802c_542a), 44);
+}
@@ -0,0 +1,174 @@
+use crate::error::Result;
+use crate::eval::CompResult;
+use crate::show::PresentNum;
+
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Numbers are written as strings, since most JSON parsers can't hold 128-bit integers
+fn num_fields(kind: &str, i: i128) -> String {
+    format!(
+        "\"kind\":\"{}\",\"value\":{{\"dec\":\"{}\",\"hex\":\"{}\",\"bin\":\"{}\"}}",
+        kind,
+        i.as_dec(true),
+        i.as_hex(true),
+        i.as_bin(true).0
+    )
+}
+
+fn value_fields(res: &CompResult) -> String {
+    match res {
+        CompResult::Num(i) => num_fields("number", *i),
+        CompResult::Typed(i, ty) => format!(
+            "\"kind\":\"number\",\"type\":\"{}\",\"value\":{{\"dec\":\"{}\",\"hex\":\"{:x}\",\"bin\":\"{:b}\"}}",
+            ty,
+            ty.fmt_dec(*i),
+            ty.raw(*i),
+            ty.raw(*i)
+        ),
+        CompResult::Func(..) | CompResult::Builtin(_) => format!(
+            "\"kind\":\"function\",\"value\":{}",
+            escape(&res.to_string())
+        ),
+        CompResult::Bytes(bytes) => format!(
+            "\"kind\":\"bytes\",\"value\":{{\"hex\":\"{}\",\"str\":{}}}",
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>(),
+            escape(&res.to_string())
+        ),
+        CompResult::Tuple(items) => format!(
+            "\"kind\":\"tuple\",\"value\":[{}]",
+            items
+                .iter()
+                .map(|item| format!("{{{}}}", value_fields(item)))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+        CompResult::Layout(layout) => format!(
+            "\"kind\":\"layout\",\"value\":{}",
+            escape(&layout.to_string())
+        ),
+        CompResult::Table(table) => format!(
+            "\"kind\":\"table\",\"value\":{}",
+            escape(&table.to_string())
+        ),
+        CompResult::Named(i, names) => format!(
+            "{},\"names\":{}",
+            num_fields("named", *i),
+            escape(names)
+        ),
+        CompResult::Decoded(i, layout) => format!(
+            "{},\"layout\":{},\"fields\":[{}]",
+            num_fields("decoded", *i),
+            escape(&layout.name),
+            layout
+                .fields
+                .iter()
+                .map(|field| format!(
+                    "{{\"name\":{},\"hi\":{},\"lo\":{},\"value\":\"{}\"}}",
+                    escape(&field.name),
+                    field.hi,
+                    field.lo,
+                    field.extract(*i)
+                ))
+                .collect::<Vec<String>>()
+                .join(",")
+        ),
+    }
+}
+
+// Serializes the result of evaluating `input` as a single-line JSON object
+pub fn to_json(input: &str, res: &Result<Option<CompResult>>) -> String {
+    let fields = match res {
+        Ok(Some(res)) => value_fields(res),
+        Ok(None) => String::from("\"kind\":\"none\""),
+        Err(err) => format!(
+            "\"error\":{{\"kind\":\"{}\",\"message\":{}}}",
+            err.kind(),
+            escape(&err.to_string())
+        ),
+    };
+    format!("{{\"input\":{},{}}}", escape(input.trim()), fields)
+}
+
+#[test]
+fn test_json() {
+    use crate::error::Error;
+    use crate::eval::{eval_expr, Context};
+    use crate::layout::Layout;
+    let mut ctx = Context::new();
+    let mut json = |expr: &str| to_json(expr, &eval_expr(expr, &mut ctx));
+    assert_eq!(
+        json(" 0x1f "),
+        "{\"input\":\"0x1f\",\"kind\":\"number\",\"value\":{\"dec\":\"31\",\"hex\":\"1f\",\"bin\":\"11111\"}}"
+    );
+    assert_eq!(
+        json("-1 as i8"),
+        "{\"input\":\"-1 as i8\",\"kind\":\"number\",\"type\":\"i8\",\
+         \"value\":{\"dec\":\"-1\",\"hex\":\"ff\",\"bin\":\"11111111\"}}"
+    );
+    assert_eq!(
+        json("$f = |$x| $x"),
+        "{\"input\":\"$f = |$x| $x\",\"kind\":\"none\"}"
+    );
+    assert_eq!(
+        json("$f"),
+        "{\"input\":\"$f\",\"kind\":\"function\",\"value\":\"|$x| $x\"}"
+    );
+    assert_eq!(
+        json("bytes(0x4122, 2)"),
+        "{\"input\":\"bytes(0x4122, 2)\",\"kind\":\"bytes\",\
+         \"value\":{\"hex\":\"4122\",\"str\":\"\\\"A\\\\\\\"\\\"\"}}"
+    );
+    assert_eq!(
+        json("(1, 2)"),
+        "{\"input\":\"(1, 2)\",\"kind\":\"tuple\",\"value\":[\
+         {\"kind\":\"number\",\"value\":{\"dec\":\"1\",\"hex\":\"1\",\"bin\":\"1\"}},\
+         {\"kind\":\"number\",\"value\":{\"dec\":\"2\",\"hex\":\"2\",\"bin\":\"10\"}}]}"
+    );
+    ctx.define(CompResult::Layout(
+        Layout::parse("CTRL { EN[0], MODE[2:1] }").unwrap(),
+    ));
+    let mut json = |expr: &str| to_json(expr, &eval_expr(expr, &mut ctx));
+    assert_eq!(
+        json("CTRL"),
+        "{\"input\":\"CTRL\",\"kind\":\"layout\",\"value\":\"CTRL { MODE[2:1], EN[0] }\"}"
+    );
+    assert_eq!(
+        json("decode(CTRL, 5)"),
+        "{\"input\":\"decode(CTRL, 5)\",\"kind\":\"decoded\",\
+         \"value\":{\"dec\":\"5\",\"hex\":\"5\",\"bin\":\"101\"},\"layout\":\"CTRL\",\"fields\":[\
+         {\"name\":\"MODE\",\"hi\":2,\"lo\":1,\"value\":\"2\"},\
+         {\"name\":\"EN\",\"hi\":0,\"lo\":0,\"value\":\"1\"}]}"
+    );
+    assert_eq!(
+        json("decode(PROT, PROT_READ | 8)"),
+        "{\"input\":\"decode(PROT, PROT_READ | 8)\",\"kind\":\"named\",\
+         \"value\":{\"dec\":\"9\",\"hex\":\"9\",\"bin\":\"1001\"},\"names\":\"PROT_READ|0x8 (unknown)\"}"
+    );
+    assert_eq!(
+        json("1 +"),
+        "{\"input\":\"1 +\",\"error\":{\"kind\":\"expr_parse\",\
+         \"message\":\"Could not parse + expression\"}}"
+    );
+    assert_eq!(
+        to_json(":nope", &Err(Error::CommandParseError(String::from("nope")))),
+        "{\"input\":\":nope\",\"error\":{\"kind\":\"command_parse\",\"message\":\"Unknown command :nope\"}}"
+    );
+}
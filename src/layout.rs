@@ -0,0 +1,215 @@
+use crate::error::{Error, Result};
+use crate::reprs::parse_num;
+use std::fmt::Display;
+
+// A named bit range, `hi` and `lo` are inclusive
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub hi: u32,
+    pub lo: u32,
+}
+
+impl Field {
+    pub fn extract(&self, x: i128) -> u128 {
+        let len = self.hi - self.lo + 1;
+        ((x as u128) >> self.lo) & (u128::MAX >> (128 - len))
+    }
+}
+
+// A register layout such as `CTRL { EN[0], MODE[3:1], DIV[15:8] }`
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Layout {
+    pub name: String,
+    pub fields: Vec<Field>,
+}
+
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn parse_bit(bit: &str) -> Option<u32> {
+    let bit = bit.trim();
+    match bit.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => bit.parse().ok(),
+    }
+    .filter(|&bit| bit < 128)
+}
+
+impl Layout {
+    // Builds a layout, sorting the fields from the highest bit down
+    pub fn new(name: &str, mut fields: Vec<Field>) -> Result<Layout> {
+        let err = |s: &str| Error::LayoutParseError(s.to_string());
+        // the layout is looked up by name, which must not read as a number like `DAC`
+        if !is_name(name) || parse_num(name).is_ok() {
+            return Err(err(name));
+        }
+        for (i, field) in fields.iter().enumerate() {
+            if !is_name(&field.name) || field.hi < field.lo || field.hi >= 128 {
+                return Err(err(&field.name));
+            }
+            if fields[..i].iter().any(|other| other.name == field.name) {
+                return Err(err(&field.name));
+            }
+        }
+        fields.sort_by(|a, b| b.hi.cmp(&a.hi).then(b.lo.cmp(&a.lo)));
+        Ok(Layout {
+            name: name.to_string(),
+            fields,
+        })
+    }
+
+    // Parses a definition written as `NAME { FIELD[hi:lo], FIELD[bit], ... }`
+    pub fn parse(def: &str) -> Result<Layout> {
+        let err = || Error::LayoutParseError(def.trim().to_string());
+        let open = def.find('{').ok_or_else(err)?;
+        let body = def[open + 1..]
+            .trim_end()
+            .strip_suffix('}')
+            .ok_or_else(err)?;
+        let fields = body
+            .split(',')
+            .filter(|field| !field.trim().is_empty())
+            .map(|field| {
+                let field = field.trim();
+                let bits = field.find('[').ok_or_else(err)?;
+                let range = field[bits + 1..].strip_suffix(']').ok_or_else(err)?;
+                let (hi, lo) = match range.find(':') {
+                    Some(i) => (parse_bit(&range[..i]), parse_bit(&range[i + 1..])),
+                    None => (parse_bit(range), parse_bit(range)),
+                };
+                Ok(Field {
+                    name: field[..bits].trim().to_string(),
+                    hi: hi.ok_or_else(err)?,
+                    lo: lo.ok_or_else(err)?,
+                })
+            })
+            .collect::<Result<Vec<Field>>>()?;
+        Self::new(def[..open].trim(), fields)
+    }
+
+    // Parses a TOML file with a table per register, where each field is a bit, a "hi:lo"
+    // string or a [hi, lo] array:
+    //
+    //     [CTRL]
+    //     EN = 0
+    //     MODE = "3:1"
+    //     DIV = [15, 8]
+    pub fn parse_toml(text: &str) -> Result<Vec<Layout>> {
+        let mut tables: Vec<(String, Vec<Field>)> = Vec::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            let err = || Error::LayoutParseError(line.to_string());
+            if line.is_empty() {
+                continue;
+            } else if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                tables.push((name.trim().trim_matches('"').to_string(), Vec::new()));
+            } else {
+                let eq = line.find('=').ok_or_else(err)?;
+                let value = line[eq + 1..].trim();
+                let (hi, lo) = if let Some(range) = value
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .or_else(|| value.strip_prefix('[').and_then(|v| v.strip_suffix(']')))
+                {
+                    let mut bits = range.split([':', ',']).map(parse_bit);
+                    let hi = bits.next().flatten();
+                    (hi, bits.next().unwrap_or(hi))
+                } else {
+                    (parse_bit(value), parse_bit(value))
+                };
+                let field = Field {
+                    name: line[..eq].trim().trim_matches('"').to_string(),
+                    hi: hi.ok_or_else(err)?,
+                    lo: lo.ok_or_else(err)?,
+                };
+                tables.last_mut().ok_or_else(err)?.1.push(field);
+            }
+        }
+        tables
+            .into_iter()
+            .map(|(name, fields)| Self::new(&name, fields))
+            .collect()
+    }
+
+    // Number of bits up to and including the highest field
+    pub fn width(&self) -> u32 {
+        self.fields
+            .iter()
+            .map(|field| field.hi + 1)
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| {
+                if field.hi == field.lo {
+                    format!("{}[{}]", field.name, field.hi)
+                } else {
+                    format!("{}[{}:{}]", field.name, field.hi, field.lo)
+                }
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{} {{ {} }}", self.name, fields.join(", "))
+    }
+}
+
+#[test]
+fn test_layout_parse() {
+    let layout = Layout::parse("CTRL { EN[0], MODE[3:1], DIV[15:8] }").unwrap();
+    assert_eq!(layout.name, "CTRL");
+    assert_eq!(layout.width(), 16);
+    assert_eq!(layout.to_string(), "CTRL { DIV[15:8], MODE[3:1], EN[0] }");
+    let values = layout
+        .fields
+        .iter()
+        .map(|field| field.extract(0x80b))
+        .collect::<Vec<u128>>();
+    assert_eq!(values, vec![0x8, 0x5, 0x1]);
+    assert_eq!(
+        Layout::parse("R {TOP[127:64],}").unwrap().fields[0].extract(-1),
+        u64::MAX.into()
+    );
+    assert!(Layout::parse("CTRL EN[0]").is_err());
+    assert!(Layout::parse("CTRL { EN[0:1] }").is_err());
+    assert!(Layout::parse("CTRL { EN[128] }").is_err());
+    assert!(Layout::parse("CTRL { EN[0], EN[1] }").is_err());
+    assert!(Layout::parse("{ EN[0] }").is_err());
+    assert!(Layout::parse("CTRL { 1EN[0] }").is_err());
+    assert!(Layout::parse("DAC { EN[0] }").is_err());
+    assert!(Layout::parse("DAC_CTRL { A[0], BE[1] }").is_ok());
+}
+
+#[test]
+fn test_layout_toml() {
+    let layouts = Layout::parse_toml(
+        "# registers\n\
+         [CTRL]\n\
+         EN = 0\n\
+         MODE = \"3:1\" # mode select\n\
+         DIV = [15, 8]\n\
+         \n\
+         [STATUS]\n\
+         READY = 0x1f\n",
+    )
+    .unwrap();
+    assert_eq!(layouts.len(), 2);
+    assert_eq!(
+        layouts[0],
+        Layout::parse("CTRL { EN[0], MODE[3:1], DIV[15:8] }").unwrap()
+    );
+    assert_eq!(layouts[1].to_string(), "STATUS { READY[31] }");
+    assert!(Layout::parse_toml("EN = 0").is_err());
+    assert!(Layout::parse_toml("[CTRL]\nEN").is_err());
+    assert!(Layout::parse_toml("[CTRL]\nEN = \"x\"").is_err());
+}
@@ -1,5 +1,26 @@
-pub(crate) mod error;
+pub(crate) mod bits;
+pub(crate) mod builtins;
+pub(crate) mod checksum;
+pub mod elf;
+pub mod encoding;
+pub mod error;
 pub mod eval;
+pub mod export;
+pub mod import;
+pub mod ioctl;
+pub mod json;
+pub mod layout;
+pub mod net;
 pub(crate) mod parser;
-pub(crate) mod reprs;
+pub mod perm;
+pub mod reprs;
+pub mod rgb;
 pub mod show;
+pub mod signs;
+pub mod tables;
+pub mod template;
+pub mod time;
+pub mod unicode;
+pub mod uuid;
+pub mod vaddr;
+pub mod wire;
@@ -0,0 +1,107 @@
+use std::convert::TryFrom;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+// Reads `aa:bb:cc:dd:ee:ff` as a 48-bit number
+fn parse_mac(s: &str) -> Option<i128> {
+    let parts = s.split(':').collect::<Vec<&str>>();
+    if parts.len() != 6 {
+        return None;
+    }
+    parts.iter().try_fold(0, |mac, part| {
+        if part.len() != 2 {
+            return None;
+        }
+        Some(mac << 8 | i128::from(u8::from_str_radix(part, 16).ok()?))
+    })
+}
+
+// Reads an IPv4, IPv6 or MAC address, IPv6 addresses taking all 128 bits
+pub fn parse_addr(s: &str) -> Option<i128> {
+    if let Ok(ip) = s.parse::<Ipv4Addr>() {
+        Some(u32::from(ip).into())
+    } else if let Some(mac) = parse_mac(s) {
+        Some(mac)
+    } else {
+        s.parse::<Ipv6Addr>().ok().map(|ip| u128::from(ip) as i128)
+    }
+}
+
+// Reads `10.0.0.0/8` or `fe80::/10` as the network and its mask
+pub fn parse_cidr(s: &str) -> Option<(i128, i128)> {
+    let (addr, prefix) = s.split_once('/')?;
+    let bits = if addr.parse::<Ipv4Addr>().is_ok() {
+        32
+    } else if addr.parse::<Ipv6Addr>().is_ok() {
+        128
+    } else {
+        return None;
+    };
+    let prefix = prefix
+        .parse::<u32>()
+        .ok()
+        .filter(|&prefix| prefix <= bits)?;
+    let host = u128::MAX.checked_shr(128 - bits + prefix).unwrap_or(0);
+    let mask = (u128::MAX >> (128 - bits) & !host) as i128;
+    Some((parse_addr(addr)? & mask, mask))
+}
+
+// The ways of writing the value as an address, IPv4 and MAC only when it fits
+pub fn describe(x: i128) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Ok(ip) = u32::try_from(x) {
+        rows.push(("IPv4", Ipv4Addr::from(ip).to_string()));
+    }
+    if (0..1 << 48).contains(&x) {
+        let bytes = x.to_be_bytes()[10..]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<String>>();
+        rows.push(("MAC", bytes.join(":")));
+    }
+    rows.push(("IPv6", Ipv6Addr::from(x as u128).to_string()));
+    rows
+}
+
+#[test]
+fn test_parse_addr() {
+    assert_eq!(parse_addr("192.168.1.10"), Some(0xc0a8_010a));
+    assert_eq!(parse_addr("fe80::1"), Some((0xfe80u128 << 112 | 1) as i128));
+    assert_eq!(parse_addr("::ffff:10.0.0.1"), Some(0xffff_0a00_0001));
+    assert_eq!(parse_addr("00:1a:2b:3c:4d:5e"), Some(0x001a_2b3c_4d5e));
+    assert_eq!(parse_addr("1.2.3"), None);
+    assert_eq!(parse_addr("0:1a:2b:3c:4d:5e"), None);
+    assert_eq!(parse_addr("fe80"), None);
+}
+
+#[test]
+fn test_parse_cidr() {
+    assert_eq!(parse_cidr("10.1.2.3/8"), Some((0x0a00_0000, 0xff00_0000)));
+    assert_eq!(parse_cidr("0.0.0.0/0"), Some((0, 0)));
+    assert_eq!(parse_cidr("1.2.3.4/32"), Some((0x0102_0304, 0xffff_ffff)));
+    assert_eq!(
+        parse_cidr("fe80::1/10"),
+        Some(((0xfe80u128 << 112) as i128, (0xffc0u128 << 112) as i128))
+    );
+    assert_eq!(parse_cidr("::1/128"), Some((1, -1)));
+    assert_eq!(parse_cidr("10.0.0.0/33"), None);
+    assert_eq!(parse_cidr("00:1a:2b:3c:4d:5e/8"), None);
+}
+
+#[test]
+fn test_describe_addr() {
+    assert_eq!(
+        describe(0xc0a8_010a),
+        vec![
+            ("IPv4", String::from("192.168.1.10")),
+            ("MAC", String::from("00:00:c0:a8:01:0a")),
+            ("IPv6", String::from("::c0a8:10a")),
+        ]
+    );
+    assert_eq!(
+        describe(-1),
+        vec![(
+            "IPv6",
+            String::from("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff")
+        )]
+    );
+}
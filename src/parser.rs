@@ -1,5 +1,11 @@
+use crate::builtins;
 use crate::error::{Error, Result};
-use crate::reprs::parse_num;
+use crate::net;
+use crate::perm;
+use crate::reprs::{parse_bytes, parse_num, show_bytes, IntType};
+use crate::rgb;
+use crate::time;
+use crate::uuid;
 use std::fmt::Display;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
@@ -16,6 +22,8 @@ pub enum Operator {
     BXor,
     BOr,
     BAnd,
+    Eq,
+    Ne,
     LShift,
     RShift,
     Lparen,
@@ -24,10 +32,14 @@ pub enum Operator {
     List,
     FnBody,
     FnCall,
+    Cast(IntType),
 }
 
 impl Display for Operator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Operator::Cast(ty) = self {
+            return write!(f, "as {}", ty);
+        }
         write!(
             f,
             "{}",
@@ -44,6 +56,8 @@ impl Display for Operator {
                 Operator::BXor => "^",
                 Operator::BOr => "|",
                 Operator::BAnd => "&",
+                Operator::Eq => "==",
+                Operator::Ne => "!=",
                 Operator::LShift => "<<",
                 Operator::RShift => ">>",
                 Operator::Lparen => "(",
@@ -52,6 +66,7 @@ impl Display for Operator {
                 Operator::List => ",",
                 Operator::FnBody => "->",
                 Operator::FnCall => ":",
+                Operator::Cast(_) => unreachable!(),
             }
         )
     }
@@ -63,15 +78,18 @@ pub enum Operand {
     Term(Operator, Box<Operand>, Box<Operand>),
     Var(String),
     FnArgs(Vec<String>),
+    Bytes(Vec<u8>),
 }
 
 impl Display for Operand {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Operand::Num(i) => write!(f, "{}", i),
+            Operand::Term(Operator::Cast(ty), _, rhs) => write!(f, "({} as {})", rhs, ty),
             Operand::Term(oper, lhs, rhs) => write!(f, "({} {} {})", lhs, oper, rhs),
             Operand::Var(name) => write!(f, "{}", name),
             Operand::FnArgs(args) => write!(f, "{:?}", args),
+            Operand::Bytes(bytes) => write!(f, "{}", show_bytes(bytes)),
         }
     }
 }
@@ -82,6 +100,8 @@ pub enum Term {
     Num(i128),
     Var(String),
     FnArgs(Vec<String>),
+    Bytes(Vec<u8>),
+    Cast(IntType),
     Lparen,
     Rparen,
 }
@@ -89,8 +109,11 @@ pub enum Term {
 pub struct Parser<'a> {
     input: &'a str,
     last_was_operand: bool,
+    // terms already read that are yet to be returned, last one first
+    pending: Vec<Term>,
 }
 
+const CAST: i32 = 80;
 const UNARY: i32 = 100_000;
 const FN_CALL: i32 = 200_000;
 
@@ -103,6 +126,8 @@ impl Parser<'_> {
             Operator::Rparen => 2,
             Operator::List => 3,
             Operator::FnBody => 5,
+            Operator::Eq => 7,
+            Operator::Ne => 7,
             Operator::BOr => 10,
             Operator::BXor => 20,
             Operator::BAnd => 30,
@@ -117,12 +142,14 @@ impl Parser<'_> {
             Operator::BNot => UNARY,
             Operator::Neg => UNARY,
             Operator::FnCall => FN_CALL,
+            Operator::Cast(_) => UNARY,
         }
     }
-    pub fn new(input: &str) -> Parser {
+    pub fn new(input: &str) -> Parser<'_> {
         Parser {
             input,
             last_was_operand: false,
+            pending: Vec::new(),
         }
     }
 
@@ -147,6 +174,67 @@ impl Parser<'_> {
         }
     }
 
+    // C-style cast prefix such as `(u16)`, returning the type and the length of the prefix
+    fn cast_ahead(&self) -> Option<(IntType, usize)> {
+        if self.last_was_operand || !self.input.starts_with('(') {
+            return None;
+        }
+        let end = self.input.find(')')?;
+        IntType::parse(self.input[1..end].trim()).map(|ty| (ty, end + 1))
+    }
+
+    // Address literal such as `10.0.0.1` or `fe80::1`, or a CIDR block like `10.0.0.0/8` which is
+    // read as a `(network, mask)` tuple, returning its terms and length
+    fn address_ahead(&self) -> Option<(Vec<Term>, usize)> {
+        let end = self
+            .input
+            .find(|nc: char| !nc.is_ascii_hexdigit() && !['.', ':', '/'].contains(&nc))
+            .unwrap_or(self.input.len());
+        let token = &self.input[..end];
+        let addr_end = token.find('/').unwrap_or(end);
+        if !token[..addr_end].contains(['.', ':']) {
+            return None;
+        }
+        if let Some((net, mask)) = net::parse_cidr(token) {
+            let terms = vec![
+                Term::Lparen,
+                Term::Num(net),
+                Term::Operator(Operator::List),
+                Term::Num(mask),
+                Term::Rparen,
+            ];
+            return Some((terms, end));
+        }
+        net::parse_addr(&token[..addr_end]).map(|addr| (vec![Term::Num(addr)], addr_end))
+    }
+
+    // UUID literal such as `123e4567-e89b-12d3-a456-426614174000`
+    fn uuid_ahead(&self) -> Option<i128> {
+        let token = self.input.get(..uuid::LEN)?;
+        let next = self.input[uuid::LEN..].chars().next();
+        if next.is_some_and(|nc| nc.is_alphanumeric() || nc == '_') {
+            return None;
+        }
+        uuid::parse(token)
+    }
+
+    // ISO-8601 literal such as `@2026-10-18T12:00:00Z`, read as Unix seconds. A `+` or `-` after
+    // the literal may start a time zone offset or an operator, so the longest part that parses
+    // is taken, returning its value and length
+    fn iso_ahead(&self) -> Option<(i128, usize)> {
+        let rest = self.input.strip_prefix('@')?;
+        let end = rest
+            .find(|nc: char| !nc.is_alphanumeric() && !['-', '+', ':', '.'].contains(&nc))
+            .unwrap_or(rest.len());
+        rest[..end]
+            .char_indices()
+            .filter(|(_, nc)| *nc == '+' || *nc == '-')
+            .map(|(i, _)| i)
+            .chain(Some(end))
+            .rev()
+            .find_map(|end| time::parse_iso(&rest[..end]).map(|secs| (secs, end + 1)))
+    }
+
     fn push_expr(operands: &mut Vec<Operand>, operators: &mut Vec<Operator>) -> Result<()> {
         let right = operands.pop();
         let left = operands.pop();
@@ -160,6 +248,7 @@ impl Parser<'_> {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl<'a> Into<Result<Operand>> for Parser<'a> {
     fn into(self) -> Result<Operand> {
         let mut operands: Vec<Operand> = Vec::new();
@@ -168,6 +257,7 @@ impl<'a> Into<Result<Operand>> for Parser<'a> {
             match i? {
                 Term::Num(num) => operands.push(Operand::Num(num)),
                 Term::Var(var_name) => operands.push(Operand::Var(var_name)),
+                Term::Bytes(bytes) => operands.push(Operand::Bytes(bytes)),
                 Term::Operator(Operator::FnCall) => {
                     operators.push(Operator::FnCall);
                     operators.push(Operator::Lparen);
@@ -189,14 +279,29 @@ impl<'a> Into<Result<Operand>> for Parser<'a> {
                 },
                 Term::Operator(oper) => {
                     if Self::op_precedence(&oper) == UNARY {
+                        // nothing to reduce before a prefix operator, so that they can be stacked
                         operands.push(Operand::Num(0));
+                    } else {
+                        while Self::op_precedence(&oper)
+                            <= Self::op_precedence(operators.last().unwrap())
+                        {
+                            Self::push_expr(&mut operands, &mut operators)?;
+                        }
                     }
-                    while Self::op_precedence(&oper)
-                        <= Self::op_precedence(&operators.last().unwrap())
-                    {
+                    operators.push(oper);
+                }
+                Term::Cast(ty) => {
+                    while Self::op_precedence(operators.last().unwrap()) > CAST {
                         Self::push_expr(&mut operands, &mut operators)?;
                     }
-                    operators.push(oper);
+                    let operand = operands
+                        .pop()
+                        .ok_or(Error::ExprParseError(Operator::Cast(ty)))?;
+                    operands.push(Operand::Term(
+                        Operator::Cast(ty),
+                        Box::new(Operand::Num(0)),
+                        Box::new(operand),
+                    ));
                 }
                 Term::FnArgs(args) => {
                     operands.push(Operand::FnArgs(args));
@@ -221,6 +326,9 @@ impl<'a> Iterator for Parser<'a> {
     type Item = Result<Term>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(term) = self.pending.pop() {
+            return Some(Ok(term));
+        }
         while let Some(c) = self.input.chars().next() {
             if c.is_whitespace() {
                 self.input = &self.input[1..];
@@ -229,15 +337,113 @@ impl<'a> Iterator for Parser<'a> {
             }
         }
         if let Some(c) = self.input.chars().next() {
-            if c.is_alphanumeric() {
+            if c == '#' {
                 self.last_was_operand = true;
-                let token =
-                    self.take_input_until(|nc| !nc.is_alphanumeric() && !nc.is_whitespace());
-                Some(parse_num(token).map(Term::Num))
+                let token = self.take_input_until(|nc| !nc.is_alphanumeric());
+                Some(
+                    rgb::parse(token)
+                        .map(Term::Num)
+                        .ok_or_else(|| Error::NumParseError(token.to_string())),
+                )
+            } else if c == '@' {
+                self.last_was_operand = true;
+                match self.iso_ahead() {
+                    Some((secs, len)) => {
+                        self.input = &self.input[len..];
+                        Some(Ok(Term::Num(secs)))
+                    }
+                    None => {
+                        let token = self.take_input_until(char::is_whitespace);
+                        Some(Err(Error::NumParseError(token.to_string())))
+                    }
+                }
+            } else if let Some((mode, len)) = perm::parse_symbolic(self.input) {
+                self.input = &self.input[len..];
+                self.last_was_operand = true;
+                Some(Ok(Term::Num(mode)))
+            } else if let Some(uuid) = self.uuid_ahead() {
+                self.input = &self.input[uuid::LEN..];
+                self.last_was_operand = true;
+                Some(Ok(Term::Num(uuid)))
+            } else if let Some((mut terms, len)) = self.address_ahead() {
+                self.input = &self.input[len..];
+                self.last_was_operand = true;
+                terms.reverse();
+                self.pending = terms;
+                self.pending.pop().map(Ok)
+            } else if c.is_alphanumeric() || c == '_' {
+                let name_end = self
+                    .input
+                    .find(|nc: char| !nc.is_alphanumeric() && nc != '_')
+                    .unwrap_or(self.input.len());
+                let name = &self.input[..name_end];
+                if name == "as" && self.last_was_operand {
+                    self.input = self.input[name_end..].trim_start();
+                    let ty_end = self
+                        .input
+                        .find(|nc: char| !nc.is_alphanumeric())
+                        .unwrap_or(self.input.len());
+                    let ty = &self.input[..ty_end];
+                    self.input = &self.input[ty_end..];
+                    return Some(
+                        IntType::parse(ty)
+                            .map(Term::Cast)
+                            .ok_or_else(|| Error::TypeParseError(ty.to_string())),
+                    );
+                }
+                self.last_was_operand = true;
+                let is_name = !c.is_ascii_digit();
+                // builtins such as `b64dec` would otherwise read as hex
+                let is_builtin = is_name && builtins::lookup(name).is_some();
+                if !is_builtin && (!is_name || !name.contains('_')) {
+                    // numbers may contain whitespace, so find the longest run of words that parses
+                    let token_end = self
+                        .input
+                        .find(|nc: char| !nc.is_alphanumeric() && !nc.is_whitespace())
+                        .unwrap_or(self.input.len());
+                    let token = &self.input[..token_end];
+                    let num = token
+                        .char_indices()
+                        .filter(|(_, nc)| nc.is_whitespace())
+                        .map(|(i, _)| i)
+                        .chain(Some(token_end))
+                        .rev()
+                        .find_map(|end| parse_num(&token[..end]).ok().map(|num| (end, num)));
+                    if let Some((end, num)) = num {
+                        self.input = &self.input[end..];
+                        return Some(Ok(Term::Num(num)));
+                    } else if !is_name {
+                        self.input = &self.input[token_end..];
+                        return Some(parse_num(token).map(Term::Num));
+                    }
+                }
+                self.input = &self.input[name_end..];
+                Some(Ok(Term::Var(name.to_string())))
             } else if c == '$' {
                 self.last_was_operand = true;
                 let token = self.take_input_until(|nc| !nc.is_alphanumeric() && nc != '_');
                 Some(Ok(Term::Var(token.to_string())))
+            } else if c == '"' {
+                self.last_was_operand = true;
+                let mut escaped = false;
+                let end = self.input[1..].find(|nc| {
+                    let closing = nc == '"' && !escaped;
+                    escaped = nc == '\\' && !escaped;
+                    closing
+                });
+                if let Some(end) = end {
+                    let token = &self.input[1..=end];
+                    self.input = &self.input[end + 2..];
+                    Some(parse_bytes(token).map(Term::Bytes))
+                } else {
+                    let token = self.input;
+                    self.input = "";
+                    Some(Err(Error::StrParseError(token.to_string())))
+                }
+            } else if let Some((ty, len)) = self.cast_ahead() {
+                self.input = &self.input[len..];
+                self.last_was_operand = false;
+                Some(Ok(Term::Operator(Operator::Cast(ty))))
             } else if c == '|' && !self.last_was_operand {
                 self.last_was_operand = false;
                 let token = self.take_input_until(|nc| nc == '|');
@@ -247,6 +453,10 @@ impl<'a> Iterator for Parser<'a> {
                     .collect::<Vec<String>>();
                 self.input = &self.input[1..];
                 Some(Ok(Term::FnArgs(pars)))
+            } else if self.input.starts_with("!=") {
+                self.input = &self.input[2..];
+                self.last_was_operand = false;
+                Some(Ok(Term::Operator(Operator::Ne)))
             } else {
                 let last_was_operand = self.last_was_operand;
                 let token = self.take_input_until(|nc| nc != c || c == '(' || c == ')');
@@ -268,6 +478,7 @@ impl<'a> Iterator for Parser<'a> {
                     "(" if !last_was_operand => Term::Lparen,
                     ")" => Term::Rparen,
                     "=" => Term::Operator(Operator::Assign),
+                    "==" => Term::Operator(Operator::Eq),
                     "," => Term::Operator(Operator::List),
                     _ => return Some(Err(Error::OperatorParseError(token.to_string()))),
                 };
@@ -307,6 +518,98 @@ fn test_lexer() {
     assert!(parser.next().unwrap().is_err());
 }
 
+#[test]
+fn test_lexer_literals() {
+    let terms = |s| Parser::new(s).map(|t| t.unwrap()).collect::<Vec<Term>>();
+    assert_eq!(
+        terms("10.0.0.1 + ::1"),
+        vec![
+            Term::Num(0x0a00_0001),
+            Term::Operator(Operator::Add),
+            Term::Num(1)
+        ]
+    );
+    assert_eq!(
+        terms("10.1.2.3/8 != 10.0.0.1 / 8"),
+        vec![
+            Term::Lparen,
+            Term::Num(0x0a00_0000),
+            Term::Operator(Operator::List),
+            Term::Num(0xff00_0000),
+            Term::Rparen,
+            Term::Operator(Operator::Ne),
+            Term::Num(0x0a00_0001),
+            Term::Operator(Operator::Div),
+            Term::Num(8),
+        ]
+    );
+    assert_eq!(
+        terms("ab:cd:ef:01:23:45==$x"),
+        vec![
+            Term::Num(0xabcd_ef01_2345),
+            Term::Operator(Operator::Eq),
+            Term::Var(String::from("$x")),
+        ]
+    );
+    assert!(Parser::new("1.2.3").any(|t| t.is_err()));
+    assert_eq!(
+        terms("123e4567-e89b-12d3-a456-426614174000-1"),
+        vec![
+            Term::Num(0x123e_4567_e89b_12d3_a456_4266_1417_4000),
+            Term::Operator(Operator::Sub),
+            Term::Num(1),
+        ]
+    );
+    assert_eq!(
+        terms("@2026-10-18T12:00:00Z-60 - @1970-01-02"),
+        vec![
+            Term::Num(1_792_324_800),
+            Term::Operator(Operator::Sub),
+            Term::Num(60),
+            Term::Operator(Operator::Sub),
+            Term::Num(86_400),
+        ]
+    );
+    assert_eq!(
+        terms("@2026-10-18T14:00+02:00"),
+        vec![Term::Num(1_792_324_800)]
+    );
+    assert!(Parser::new("@2026-02-30").any(|t| t.is_err()));
+    assert_eq!(
+        terms("u=rwx,g=rx | 0o1000"),
+        vec![
+            Term::Num(0o750),
+            Term::Operator(Operator::BOr),
+            Term::Num(0o1000)
+        ]
+    );
+    assert_eq!(
+        terms("a-c"),
+        vec![
+            Term::Num(0xa),
+            Term::Operator(Operator::Sub),
+            Term::Num(0xc)
+        ]
+    );
+    assert_eq!(
+        terms("#ff8000|#01020304"),
+        vec![
+            Term::Num(0xff8000),
+            Term::Operator(Operator::BOr),
+            Term::Num(0x0401_0203)
+        ]
+    );
+    assert!(Parser::new("#ff80").any(|t| t.is_err()));
+    assert_eq!(
+        terms("12345678-1234"),
+        vec![
+            Term::Num(12345678),
+            Term::Operator(Operator::Sub),
+            Term::Num(1234)
+        ]
+    );
+}
+
 #[test]
 fn test_parser_simple() {
     let oper: Operand = Result::from(Parser::new("1").into()).unwrap();
@@ -438,3 +741,42 @@ fn test_create_func() {
         "(([\"$x\"] -> ($x + $y)) : ((1 , 2) , 3))"
     );
 }
+
+#[test]
+fn test_lexer_names() {
+    let parser = Parser::new("crc16_ccitt(\"a\\\"b\", ab cd) + a as u8");
+    let terms = parser.map(|t| t.unwrap()).collect::<Vec<Term>>();
+    assert_eq!(
+        format!("{:?}", terms),
+        "[Var(\"crc16_ccitt\"), Operator(FnCall), Bytes([97, 34, 98]), \
+         Operator(List), Num(43981), Rparen, Operator(Add), Num(10), Cast(IntType { bits: 8, signed: false })]"
+    );
+
+    let parser = Parser::new("b64dec(\"AQI=\") + b64dec");
+    let terms = parser.map(|t| t.unwrap()).collect::<Vec<Term>>();
+    assert_eq!(
+        format!("{:?}", terms),
+        "[Var(\"b64dec\"), Operator(FnCall), Bytes([65, 81, 73, 61]), Rparen, Operator(Add), Var(\"b64dec\")]"
+    );
+
+    let mut parser = Parser::new("\"abc");
+    assert!(parser.next().unwrap().is_err());
+}
+
+#[test]
+fn test_parser_cast() {
+    let oper: Operand = Result::from(Parser::new("1 + 2 * $x as i8").into()).unwrap();
+    assert_eq!(format!("{}", oper), "(1 + (2 * ($x as i8)))");
+
+    let oper: Operand = Result::from(Parser::new("-$f(1) as u16 << 2").into()).unwrap();
+    assert_eq!(format!("{}", oper), "(((0 - ($f : 1)) as u16) << 2)");
+
+    let oper: Operand = Result::from(Parser::new("(i12)$x + (u8)-1").into()).unwrap();
+    assert_eq!(format!("{}", oper), "(($x as i12) + ((0 - 1) as u8))");
+
+    let oper: Operand = Result::from(Parser::new("~-1").into()).unwrap();
+    assert_eq!(format!("{}", oper), "(0 ~ (0 - 1))");
+
+    assert!(Result::from(Parser::new("1 as x8").into()).is_err());
+    assert!(Result::from(Parser::new("as u8").into()).is_err());
+}
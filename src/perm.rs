@@ -0,0 +1,130 @@
+const SETUID: i128 = 0o4000;
+const SETGID: i128 = 0o2000;
+const STICKY: i128 = 0o1000;
+
+// The permission bits of each class, and the special bit shown in its `x` column
+const CLASSES: [(char, i128, i128, char); 3] = [
+    ('u', 0o700, SETUID, 's'),
+    ('g', 0o070, SETGID, 's'),
+    ('o', 0o007, STICKY, 't'),
+];
+
+// Writes a mode like `rwsr-x--- (04750)` the way `ls -l` shows it
+pub fn show(x: i128) -> Option<String> {
+    if !(0..=0o7777).contains(&x) {
+        return None;
+    }
+    let mut s = String::new();
+    for (_, mask, special, mark) in CLASSES.iter() {
+        let bits = (x & mask) >> mask.trailing_zeros();
+        s.push(if bits & 4 != 0 { 'r' } else { '-' });
+        s.push(if bits & 2 != 0 { 'w' } else { '-' });
+        s.push(match (bits & 1 != 0, x & special != 0) {
+            (true, true) => *mark,
+            (false, true) => mark.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    Some(format!("{} ({:04o})", s, x))
+}
+
+// Reads one `who op perms` clause like `ug+rw`, returning its bits and the operator
+fn parse_clause(clause: &str) -> Option<(i128, char)> {
+    let op_at = clause.find(['=', '+', '-'])?;
+    let (who, rest) = clause.split_at(op_at);
+    let op = rest.chars().next()?;
+    let perms = &rest[1..];
+    if (op != '=' && perms.is_empty()) || !who.chars().all(|c| "ugoa".contains(c)) {
+        return None;
+    }
+    let classes = CLASSES
+        .iter()
+        .filter(|(class, ..)| who.is_empty() || who.contains('a') || who.contains(*class))
+        .collect::<Vec<_>>();
+    let mut bits = 0;
+    for c in perms.chars() {
+        for (_, mask, special, mark) in classes.iter() {
+            bits |= match c {
+                'r' => mask & 0o444,
+                'w' => mask & 0o222,
+                'x' => mask & 0o111,
+                c if c == *mark => *special,
+                _ if "rwxst".contains(c) => 0,
+                _ => return None,
+            };
+        }
+    }
+    Some((bits, op))
+}
+
+// Reads a symbolic mode such as `u=rwx,g=rx` starting from no permissions, returning the mode
+// and the length of the literal at the front of `s`. It must start with a class so that `= 1`
+// isn't taken as `a=` followed by a number
+pub fn parse_symbolic(s: &str) -> Option<(i128, usize)> {
+    if !s.starts_with(['u', 'g', 'o', 'a']) {
+        return None;
+    }
+    let end = s
+        .find(|c: char| !c.is_ascii_alphabetic() && !['=', '+', '-', ','].contains(&c))
+        .unwrap_or(s.len());
+    let mut mode = 0;
+    let mut len = 0;
+    for clause in s[..end].split(',') {
+        let (bits, op) = match parse_clause(clause) {
+            Some(clause) => clause,
+            None if len > 0 => break,
+            None => return None,
+        };
+        mode = match op {
+            '+' => mode | bits,
+            '-' => mode & !bits,
+            // `=` only replaces the bits of the classes it names
+            _ => {
+                let who = &clause[..clause.find('=')?];
+                let cleared = CLASSES
+                    .iter()
+                    .filter(|(class, ..)| {
+                        who.is_empty() || who.contains('a') || who.contains(*class)
+                    })
+                    .fold(0, |cleared, (_, mask, special, _)| cleared | mask | special);
+                mode & !cleared | bits
+            }
+        };
+        len += if len == 0 { 0 } else { 1 } + clause.len();
+    }
+    let next = s[len..].chars().next();
+    if next.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((mode, len))
+}
+
+#[test]
+fn test_show_perm() {
+    assert_eq!(show(0o750).unwrap(), "rwxr-x--- (0750)");
+    assert_eq!(show(0o4755).unwrap(), "rwsr-xr-x (4755)");
+    assert_eq!(show(0o2640).unwrap(), "rw-r-S--- (2640)");
+    assert_eq!(show(0o1777).unwrap(), "rwxrwxrwt (1777)");
+    assert_eq!(show(0o1776).unwrap(), "rwxrwxrwT (1776)");
+    assert_eq!(show(0).unwrap(), "--------- (0000)");
+    assert_eq!(show(0o10000), None);
+    assert_eq!(show(-1), None);
+}
+
+#[test]
+fn test_parse_symbolic() {
+    assert_eq!(parse_symbolic("u=rwx,g=rx"), Some((0o750, 10)));
+    assert_eq!(parse_symbolic("a+r,u+w"), Some((0o644, 7)));
+    assert_eq!(parse_symbolic("ug=rwx,o=,g-w"), Some((0o750, 13)));
+    assert_eq!(parse_symbolic("u=rwxs,+t"), Some((0o5700, 9)));
+    assert_eq!(parse_symbolic("a=rwx,go-w"), Some((0o755, 10)));
+    assert_eq!(parse_symbolic("u=rw, 1"), Some((0o600, 4)));
+    assert_eq!(parse_symbolic("u=rw)"), Some((0o600, 4)));
+    assert_eq!(parse_symbolic("a-b"), None);
+    assert_eq!(parse_symbolic("a- 1"), None);
+    assert_eq!(parse_symbolic("u=rwq"), None);
+    assert_eq!(parse_symbolic("x=r"), None);
+    assert_eq!(parse_symbolic("= 1"), None);
+    assert_eq!(parse_symbolic("+x"), None);
+}
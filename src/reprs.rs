@@ -1,8 +1,109 @@
 use crate::error::{Error, Result};
+use crate::net;
+use crate::perm;
+use crate::rgb;
+use crate::time;
+use crate::uuid;
+use std::fmt::Display;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct IntType {
+    pub bits: u32,
+    pub signed: bool,
+}
+
+impl IntType {
+    pub fn new(bits: u32, signed: bool) -> Option<IntType> {
+        if (1..=128).contains(&bits) {
+            Some(IntType { bits, signed })
+        } else {
+            None
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<IntType> {
+        let signed = match name.chars().next()? {
+            'i' => true,
+            'u' => false,
+            _ => return None,
+        };
+        let bits = name[1..].parse().ok()?;
+        Self::new(bits, signed)
+    }
+
+    pub fn raw(self, x: i128) -> u128 {
+        (x as u128) & (u128::MAX >> (128 - self.bits))
+    }
+
+    pub fn wrap(self, x: i128) -> i128 {
+        let shift = 128 - self.bits;
+        if self.signed {
+            (x << shift) >> shift
+        } else {
+            self.raw(x) as i128
+        }
+    }
+
+    // The smallest standard integer type that holds `x`
+    pub fn fitting(x: i128) -> IntType {
+        [8, 16, 32, 64, 128]
+            .iter()
+            .map(|&bits| IntType {
+                bits,
+                signed: x < 0,
+            })
+            .find(|ty| ty.fits(x))
+            .unwrap()
+    }
+
+    pub fn byte_len(self) -> usize {
+        (self.bits as usize).div_ceil(8)
+    }
+
+    // Big-endian bytes of `x` at this width
+    pub fn to_bytes(self, x: i128) -> Vec<u8> {
+        self.raw(x).to_be_bytes()[16 - self.byte_len()..].to_vec()
+    }
+
+    pub fn fits(self, x: i128) -> bool {
+        self.wrap(x) == x && (self.signed || x >= 0)
+    }
+
+    pub fn fmt_dec(self, x: i128) -> String {
+        if self.signed {
+            self.wrap(x).to_string()
+        } else {
+            self.raw(x).to_string()
+        }
+    }
+}
+
+impl Display for IntType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", if self.signed { 'i' } else { 'u' }, self.bits)
+    }
+}
 
 pub fn parse_num(num_str: &str) -> Result<i128> {
     let mut radix: Option<u32> = None;
     let mut slice = num_str.trim();
+    if slice.starts_with('#') {
+        return rgb::parse(slice).ok_or_else(|| Error::NumParseError(num_str.to_string()));
+    }
+    if let Some(iso) = slice.strip_prefix('@') {
+        return time::parse_iso(iso).ok_or_else(|| Error::NumParseError(num_str.to_string()));
+    }
+    if let Some((mode, len)) = perm::parse_symbolic(slice) {
+        if len == slice.len() {
+            return Ok(mode);
+        }
+    }
+    if let Some(uuid) = uuid::parse(slice) {
+        return Ok(uuid);
+    }
+    if slice.contains(['.', ':']) {
+        return net::parse_addr(slice).ok_or_else(|| Error::NumParseError(num_str.to_string()));
+    }
     if slice.starts_with("0x") {
         slice = &slice[2..];
         radix = Some(16);
@@ -12,6 +113,9 @@ pub fn parse_num(num_str: &str) -> Result<i128> {
     } else if slice.starts_with("0b") {
         slice = &slice[2..];
         radix = Some(2);
+    } else if slice.starts_with("0o") {
+        slice = &slice[2..];
+        radix = Some(8);
     } else if slice.ends_with('b') {
         slice = &slice[..slice.len() - 1];
         radix = Some(2);
@@ -27,6 +131,52 @@ pub fn parse_num(num_str: &str) -> Result<i128> {
     }
 }
 
+pub fn parse_bytes(s: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        bytes.push(match chars.next() {
+            Some('n') => b'\n',
+            Some('r') => b'\r',
+            Some('t') => b'\t',
+            Some('0') => 0,
+            Some('\\') => b'\\',
+            Some('"') => b'"',
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                u8::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| hex.len() == 2)
+                    .ok_or_else(|| Error::StrParseError(s.to_string()))?
+            }
+            _ => return Err(Error::StrParseError(s.to_string())),
+        });
+    }
+    Ok(bytes)
+}
+
+pub fn show_bytes(bytes: &[u8]) -> String {
+    let mut s = String::from("\"");
+    for &b in bytes {
+        match b {
+            b'\n' => s.push_str("\\n"),
+            b'\r' => s.push_str("\\r"),
+            b'\t' => s.push_str("\\t"),
+            b'\\' => s.push_str("\\\\"),
+            b'"' => s.push_str("\\\""),
+            0x20..=0x7e => s.push(b as char),
+            _ => s.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    s.push('"');
+    s
+}
+
 #[test]
 fn test_parse_hex() {
     assert_eq!(parse_num("0xc").unwrap(), 12);
@@ -48,6 +198,14 @@ fn test_parse_bin() {
     assert!(parse_num("1010012b").is_err());
 }
 
+#[test]
+fn test_parse_oct() {
+    assert_eq!(parse_num("0o755").unwrap(), 0o755);
+    assert_eq!(parse_num("0o 1 777").unwrap(), 0o1777);
+    assert!(parse_num("0o8").is_err());
+    assert_eq!(parse_num("u=rwx,g=rx").unwrap(), 0o750);
+}
+
 #[test]
 fn test_parse_dec() {
     assert_eq!(parse_num("01010").unwrap(), 1010);
@@ -55,3 +213,40 @@ fn test_parse_dec() {
     assert_eq!(parse_num("-4321").unwrap(), -4321);
     assert_eq!(parse_num(" 1 23   4 ").unwrap(), 1234);
 }
+
+#[test]
+fn test_parse_bytes() {
+    assert_eq!(parse_bytes("abc").unwrap(), b"abc");
+    assert_eq!(parse_bytes("a\\n\\x00\\xff\\\"").unwrap(), b"a\n\x00\xff\"");
+    assert!(parse_bytes("\\x1").is_err());
+    assert!(parse_bytes("\\q").is_err());
+    assert_eq!(show_bytes(b"a\n\x00\xff\""), "\"a\\n\\x00\\xff\\\"\"");
+}
+
+#[test]
+fn test_int_type() {
+    let i8_t = IntType::parse("i8").unwrap();
+    let u12_t = IntType::parse("u12").unwrap();
+    let u128_t = IntType::parse("u128").unwrap();
+    assert_eq!(i8_t.wrap(0xff), -1);
+    assert_eq!(i8_t.wrap(0x17f), 127);
+    assert_eq!(i8_t.raw(-1), 0xff);
+    assert_eq!(u12_t.wrap(-1), 0xfff);
+    assert_eq!(IntType::parse("i12").unwrap().wrap(0x800), -2048);
+    assert_eq!(u128_t.fmt_dec(-1), u128::MAX.to_string());
+    assert_eq!(i8_t.fmt_dec(0x80), "-128");
+    assert_eq!(u12_t.to_string(), "u12");
+    assert_eq!(IntType::parse("u0"), None);
+    assert_eq!(IntType::parse("i129"), None);
+    assert_eq!(IntType::parse("x8"), None);
+    assert!(i8_t.fits(-128));
+    assert!(!i8_t.fits(128));
+    assert!(!u128_t.fits(-1));
+    assert!(u12_t.fits(0xfff));
+    assert_eq!(IntType::fitting(0xff), IntType::parse("u8").unwrap());
+    assert_eq!(IntType::fitting(0x100), IntType::parse("u16").unwrap());
+    assert_eq!(IntType::fitting(-129), IntType::parse("i16").unwrap());
+    assert_eq!(IntType::fitting(i128::MIN), IntType::parse("i128").unwrap());
+    assert_eq!(u12_t.to_bytes(-1), vec![0x0f, 0xff]);
+    assert_eq!(i8_t.to_bytes(0x1234), vec![0x34]);
+}
@@ -0,0 +1,90 @@
+use std::convert::TryFrom;
+
+// Reads `#RRGGBB` as `0xRRGGBB` and `#RRGGBBAA` as `0xAARRGGBB`, so that the color channels
+// keep their place and the alpha sits above them as in ARGB8888
+pub fn parse(s: &str) -> Option<i128> {
+    let hex = s.strip_prefix('#')?;
+    if (hex.len() != 6 && hex.len() != 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let x = i128::from_str_radix(hex, 16).ok()?;
+    Some(if hex.len() == 8 {
+        (x & 0xff) << 24 | x >> 8
+    } else {
+        x
+    })
+}
+
+fn channel(x: i128) -> Option<i128> {
+    Some(x).filter(|x| (0..=0xff).contains(x))
+}
+
+// Packs 8-bit channels into 5, 6 and 5 bits
+pub fn rgb565(r: i128, g: i128, b: i128) -> Option<i128> {
+    let (r, g, b) = (channel(r)?, channel(g)?, channel(b)?);
+    Some((r >> 3) << 11 | (g >> 2) << 5 | b >> 3)
+}
+
+// Widens the channels of an RGB565 value back to 8 bits, repeating their top bits in the low ones
+pub fn unpack_rgb565(x: i128) -> Option<(i128, i128, i128)> {
+    let x = u16::try_from(x).ok().map(i128::from)?;
+    let (r, g, b) = (x >> 11, (x >> 5) & 0x3f, x & 0x1f);
+    Some((r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2))
+}
+
+pub fn argb8888(a: i128, r: i128, g: i128, b: i128) -> Option<i128> {
+    Some(channel(a)? << 24 | channel(r)? << 16 | channel(g)? << 8 | channel(b)?)
+}
+
+// The channels of an `0xAARRGGBB` value, followed by a true-color swatch when `color` is set.
+// The color channels are always the low 24 bits, and an alpha of 0 is left out as it can't be
+// told apart from a plain `0xRRGGBB`
+pub fn describe(x: i128, color: bool) -> Vec<(&'static str, String)> {
+    let (name, a) = match x {
+        0..=0xff_ffff => ("RGB", None),
+        0x100_0000..=0xffff_ffff => ("ARGB", Some(x >> 24)),
+        _ => return Vec::new(),
+    };
+    let (r, g, b) = ((x >> 16) & 0xff, (x >> 8) & 0xff, x & 0xff);
+    let mut s = a.map_or_else(String::new, |a| format!("a={} ", a));
+    s.push_str(&format!("r={} g={} b={}", r, g, b));
+    if color {
+        s.push_str(&format!("  \u{1b}[38;2;{};{};{}m████\u{1b}[0m", r, g, b));
+    }
+    vec![(name, s)]
+}
+
+#[test]
+fn test_rgb() {
+    assert_eq!(parse("#ff8000"), Some(0xff8000));
+    assert_eq!(parse("#FF800080"), Some(0x80ff_8000));
+    assert_eq!(parse("#0000ff80"), Some(0x8000_00ff));
+    assert_eq!(parse("#fff"), None);
+    assert_eq!(parse("#ff80zz"), None);
+    assert_eq!(rgb565(0xff, 0x80, 0x00), Some(0xfc00));
+    assert_eq!(rgb565(0xff, 0xff, 0xff), Some(0xffff));
+    assert_eq!(rgb565(0x100, 0, 0), None);
+    assert_eq!(unpack_rgb565(0xfc00), Some((0xff, 0x82, 0x00)));
+    assert_eq!(unpack_rgb565(0xffff), Some((0xff, 0xff, 0xff)));
+    assert_eq!(unpack_rgb565(0x1_0000), None);
+    assert_eq!(argb8888(0x80, 0xff, 0x80, 0), Some(0x80ff_8000));
+    assert_eq!(argb8888(0, -1, 0, 0), None);
+    assert_eq!(
+        describe(0xff8000, false),
+        vec![("RGB", String::from("r=255 g=128 b=0"))]
+    );
+    assert_eq!(
+        describe(0x80ff_8000, true),
+        vec![(
+            "ARGB",
+            String::from("a=128 r=255 g=128 b=0  \u{1b}[38;2;255;128;0m████\u{1b}[0m")
+        )]
+    );
+    let argb = argb8888(0x80, 0, 0, 0xff).unwrap();
+    assert_eq!(parse("#0000ff80"), Some(argb));
+    assert_eq!(
+        describe(argb, false),
+        vec![("ARGB", String::from("a=128 r=0 g=0 b=255"))]
+    );
+    assert_eq!(describe(1 << 32, true), vec![]);
+}
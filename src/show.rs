@@ -1,73 +1,484 @@
+use crate::error::{Error, Result};
+use crate::layout::Layout;
+use crate::reprs::IntType;
+use crate::uuid;
+use std::env;
+use std::io::{self, IsTerminal};
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct Group {
+    pub size: usize,
+    pub sep: char,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct Grouping {
+    pub dec: Group,
+    pub hex: Group,
+    pub bin: Group,
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Grouping {
+            dec: Group { size: 3, sep: ' ' },
+            hex: Group { size: 3, sep: ' ' },
+            bin: Group { size: 4, sep: ' ' },
+        }
+    }
+}
+
+impl Grouping {
+    // Sets the group size and optionally the separator of one base, `size` 0 disables grouping
+    pub fn set(&mut self, base: &str, size: &str, sep: Option<&str>) -> Result<()> {
+        let group = match base {
+            "dec" => &mut self.dec,
+            "hex" => &mut self.hex,
+            "bin" => &mut self.bin,
+            _ => return Err(Error::OptionParseError(base.to_string())),
+        };
+        let size = size
+            .parse()
+            .ok()
+            .filter(|&size| size <= 64 && (base != "bin" || size >= 3))
+            .ok_or_else(|| Error::OptionParseError(size.to_string()))?;
+        let sep = match sep {
+            None => group.sep,
+            Some("space") => ' ',
+            Some("locale") => locale_separator(),
+            Some(sep) if sep.len() == 1 && !sep.starts_with(char::is_alphanumeric) => {
+                sep.chars().next().unwrap()
+            }
+            Some(sep) => return Err(Error::OptionParseError(sep.to_string())),
+        };
+        *group = Group { size, sep };
+        Ok(())
+    }
+}
+
+// Thousands separator of the locale set in the environment
+pub fn locale_separator() -> char {
+    let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .unwrap_or_default();
+    let (lang, region) = match locale.find('_') {
+        Some(i) => (&locale[..i], locale[i + 1..].get(..2).unwrap_or("")),
+        None => (&locale[..], ""),
+    };
+    match (lang, region) {
+        (_, "CH") | (_, "LI") => '\'',
+        ("de", _)
+        | ("es", _)
+        | ("it", _)
+        | ("nl", _)
+        | ("pt", _)
+        | ("da", _)
+        | ("id", _)
+        | ("tr", _)
+        | ("el", _) => '.',
+        ("fr", _)
+        | ("ru", _)
+        | ("sv", _)
+        | ("pl", _)
+        | ("cs", _)
+        | ("fi", _)
+        | ("nb", _)
+        | ("uk", _)
+        | ("hu", _)
+        | ("sk", _) => ' ',
+        _ => ',',
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(s: &str) -> Result<ColorChoice> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(Error::OptionParseError(s.to_string())),
+        }
+    }
+
+    // Auto enables color only for a terminal, unless NO_COLOR is set
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+// SGR parameters of each row, such as "36" or "1;38;5;208"
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Theme {
+    pub dec: String,
+    pub hex: String,
+    pub bin: String,
+    pub ruler: String,
+    pub bytes: String,
+    pub fields: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            dec: String::from("36"),
+            hex: String::from("92"),
+            bin: String::from("91"),
+            ruler: String::from("90"),
+            bytes: String::from("33"),
+            fields: String::from("35"),
+        }
+    }
+}
+
+impl Theme {
+    // Sets row colors from a list like `dec=cyan,hex=bright-green,ruler=2;37`
+    pub fn set(&mut self, spec: &str) -> Result<()> {
+        for item in spec.split(',').filter(|item| !item.trim().is_empty()) {
+            let mut parts = item.splitn(2, '=').map(str::trim);
+            let row = match parts.next() {
+                Some("dec") => &mut self.dec,
+                Some("hex") => &mut self.hex,
+                Some("bin") => &mut self.bin,
+                Some("ruler") => &mut self.ruler,
+                Some("bytes") => &mut self.bytes,
+                Some("fields") => &mut self.fields,
+                _ => return Err(Error::OptionParseError(item.to_string())),
+            };
+            let color = parts.next().unwrap_or("");
+            *row = match Self::color_code(color) {
+                Some(code) => code,
+                None => return Err(Error::OptionParseError(color.to_string())),
+            };
+        }
+        Ok(())
+    }
+
+    fn color_code(color: &str) -> Option<String> {
+        const NAMES: [&str; 8] = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        let (base, name) = match color.strip_prefix("bright-") {
+            Some(name) => (90, name),
+            None => (30, color),
+        };
+        if let Some(i) = NAMES.iter().position(|&n| n == name) {
+            Some((base + i).to_string())
+        } else if !color.is_empty() && color.chars().all(|c| c.is_ascii_digit() || c == ';') {
+            Some(color.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ShowOpts {
+    pub grouping: Grouping,
+    pub color: bool,
+    pub theme: Theme,
+}
+
+impl Default for ShowOpts {
+    fn default() -> Self {
+        ShowOpts {
+            grouping: Grouping::default(),
+            color: true,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl ShowOpts {
+    pub fn paint(&self, color: &str, s: &str) -> String {
+        if self.color {
+            format!("\x1B[{}m{}\x1B[0m", color, s)
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+// A row shown under the ruler
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum ExtraRow {
+    // Bits hi..=lo of the bin row, in the same columns, and a label
+    Field(u32, u32, String),
+    // A named row, like `Sym   start_kernel+0x1c/0x80`
+    Label(String, String),
+}
+
 pub trait PresentNum {
-    fn as_dec(&self, simple: bool) -> String;
-    fn as_hex(&self, simple: bool) -> String;
-    fn as_bin(&self, simple: bool) -> (String, String);
-    fn show_all(&self) -> String;
-    fn group_str(s: String, every: usize) -> (String, String) {
+    fn as_dec(&self, simple: bool) -> String {
+        self.as_dec_with(simple, &Grouping::default())
+    }
+    fn as_hex(&self, simple: bool) -> String {
+        self.as_hex_with(simple, &Grouping::default())
+    }
+    fn as_bin(&self, simple: bool) -> (String, String) {
+        self.as_bin_with(simple, &Grouping::default())
+    }
+    fn as_dec_with(&self, simple: bool, grouping: &Grouping) -> String;
+    fn as_hex_with(&self, simple: bool, grouping: &Grouping) -> String;
+    fn as_bin_with(&self, simple: bool, grouping: &Grouping) -> (String, String);
+    fn show_all(&self) -> String {
+        self.show_with(None, &ShowOpts::default())
+    }
+    fn show_typed(&self, ty: IntType) -> String {
+        self.show_with(Some(ty), &ShowOpts::default())
+    }
+    fn show_widths(&self) -> String {
+        self.show_widths_with(&ShowOpts::default())
+    }
+    fn show_fields(&self, layout: &Layout) -> String {
+        self.show_fields_with(layout, &[], &ShowOpts::default())
+    }
+    fn show_with(&self, ty: Option<IntType>, opts: &ShowOpts) -> String;
+    fn show_widths_with(&self, opts: &ShowOpts) -> String;
+    fn show_fields_with(&self, layout: &Layout, extra: &[ExtraRow], opts: &ShowOpts) -> String;
+    fn show_extra(&self, ty: Option<IntType>, extra: &[ExtraRow], opts: &ShowOpts) -> String;
+    fn show_rows(
+        dec: String,
+        hex: String,
+        bin: (String, String),
+        bytes: &[u8],
+        extra: &[ExtraRow],
+        opts: &ShowOpts,
+    ) -> String {
+        let ruler = bin
+            .1
+            .chars()
+            .skip(bin.1.len() - bin.0.len())
+            .collect::<String>();
+        let show_bytes = |bytes: &mut dyn Iterator<Item = &u8>| {
+            let mut s = bytes
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<String>>()
+                .join(" ");
+            s.push_str("  ");
+            s
+        };
+        let le = show_bytes(&mut bytes.iter().rev());
+        let be = show_bytes(&mut bytes.iter());
+        let max_len = [&dec, &hex, &bin.0, &le]
+            .iter()
+            .map(|s| s.len())
+            .max()
+            .unwrap();
+        // keeps the digits of bits lo..=hi of the bin row, in the same columns
+        let field_bits = |hi: u32, lo: u32| {
+            let mut bit = 0;
+            let mut row = bin.0[..bin.0.len() - 2]
+                .chars()
+                .rev()
+                .map(|c| {
+                    let shown = if c.is_ascii_digit() {
+                        bit += 1;
+                        (lo..=hi).contains(&(bit - 1))
+                    } else {
+                        bit > lo && bit <= hi
+                    };
+                    if shown {
+                        c
+                    } else {
+                        ' '
+                    }
+                })
+                .collect::<Vec<char>>();
+            row.reverse();
+            row.into_iter().collect::<String>()
+        };
+        let theme = &opts.theme;
+        let mut rows = vec![
+            (&theme.dec, format!("Dec   {1:>0$}", max_len, dec)),
+            (&theme.hex, format!("Hex   {1:>0$}", max_len, hex)),
+            (&theme.bin, format!("Bin   {1:>0$}", max_len, bin.0)),
+            (&theme.ruler, format!("      {}", ruler)),
+        ];
+        rows.extend(extra.iter().map(|row| match row {
+            ExtraRow::Field(hi, lo, label) => (
+                &theme.fields,
+                format!(
+                    "      {1:>0$}  {2}",
+                    max_len - 2,
+                    field_bits(*hi, *lo),
+                    label
+                ),
+            ),
+            ExtraRow::Label(name, text) => (&theme.fields, format!("{:<6}{}", name, text)),
+        }));
+        rows.push((&theme.bytes, format!("LE    {1:>0$}", max_len, le)));
+        rows.push((&theme.bytes, format!("BE    {1:>0$}", max_len, be)));
+        rows.iter()
+            .map(|(color, row)| opts.paint(color, row))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    fn group_with(s: String, group: Group) -> (String, String) {
+        let (sign, digits) = if let Some(digits) = s.strip_prefix('-') {
+            ("-", digits)
+        } else {
+            ("", &s[..])
+        };
         let mut v: Vec<char> = Vec::new();
-        let mut ruler = String::from("----0 ");
-        for (i, c) in s.chars().rev().enumerate() {
-            if i > 0 && i % every == 0 {
-                v.push(' ');
-                ruler.insert_str(0, format!("{0:->1$}", i, every + 1).as_str());
+        let mut ruler = format!("{0:->1$} ", 0, group.size + 1);
+        for (i, c) in digits.chars().rev().enumerate() {
+            if group.size > 0 && i > 0 && i % group.size == 0 {
+                v.push(group.sep);
+                ruler.insert_str(0, format!("{0:->1$}", i, group.size + 1).as_str());
             }
             v.push(c);
         }
+        v.extend(sign.chars().rev());
         (v.iter().rev().collect::<String>(), ruler)
     }
 }
 
 impl PresentNum for i128 {
-    fn as_dec(&self, simple: bool) -> String {
+    fn as_dec_with(&self, simple: bool, grouping: &Grouping) -> String {
         let dec = format!("{}", self);
         if simple {
             dec
         } else {
-            let mut s = Self::group_str(dec, 3).0;
+            let mut s = Self::group_with(dec, grouping.dec).0;
             s.push_str("  ");
             s
         }
     }
 
-    fn as_hex(&self, simple: bool) -> String {
+    fn as_hex_with(&self, simple: bool, grouping: &Grouping) -> String {
         let hex = format!("{:x}", self);
         if simple {
             hex
         } else {
-            let mut s = Self::group_str(hex, 3).0;
+            let mut s = Self::group_with(hex, grouping.hex).0;
             s.push_str(" h");
             s
         }
     }
 
-    fn as_bin(&self, simple: bool) -> (String, String) {
+    fn as_bin_with(&self, simple: bool, grouping: &Grouping) -> (String, String) {
         let bin = format!("{:b}", self);
         if simple {
             (bin, String::from(""))
         } else {
-            let mut s = Self::group_str(bin, 4);
+            let mut s = Self::group_with(bin, grouping.bin);
             s.0.push_str(" b");
             s
         }
     }
 
-    fn show_all(&self) -> String {
-        let dec = self.as_dec(false);
-        let hex = self.as_hex(false);
-        let bin = self.as_bin(false);
-        let ruler = bin
-            .1
-            .chars()
-            .skip(bin.1.len() - bin.0.len())
-            .collect::<String>();
-        let max_len = [&dec, &hex, &bin.0].iter().map(|s| s.len()).max().unwrap();
-        format!(
-            "\x1B[36mDec   {1:>0$}\x1B[0m\n\
-             \x1B[92mHex   {2:>0$}\x1B[0m\n\
-             \x1B[91mBin   {3:>0$}\x1B[0m\n\
-             \x1B[90m      {4}\x1B[0m",
-            max_len, dec, hex, &bin.0, ruler
-        )
+    fn show_with(&self, ty: Option<IntType>, opts: &ShowOpts) -> String {
+        self.show_extra(ty, &[], opts)
+    }
+
+    fn show_extra(&self, ty: Option<IntType>, extra: &[ExtraRow], opts: &ShowOpts) -> String {
+        let grouping = &opts.grouping;
+        let (dec, hex, bin) = match ty {
+            Some(ty) => {
+                let bits = ty.bits as usize;
+                (
+                    ty.fmt_dec(*self),
+                    format!("{:01$x}", ty.raw(*self), bits.div_ceil(4)),
+                    format!("{:01$b}", ty.raw(*self), bits),
+                )
+            }
+            None => (self.as_dec(true), self.as_hex(true), self.as_bin(true).0),
+        };
+        let mut dec = Self::group_with(dec, grouping.dec).0;
+        dec.push_str("  ");
+        let mut hex = Self::group_with(hex, grouping.hex).0;
+        hex.push_str(" h");
+        let mut bin = Self::group_with(bin, grouping.bin);
+        bin.0.push_str(" b");
+        // values cast to 128 bits, or positive ones that need all of them, are often UUIDs
+        let cast = ty.is_some();
+        let ty = ty.unwrap_or_else(|| IntType::fitting(*self));
+        let uuid = Some(ty)
+            .filter(|ty| ty.bits == 128 && (cast || *self >= 0))
+            .map(|ty| ExtraRow::Label(String::from("UUID"), uuid::show(ty.raw(*self) as i128)));
+        let extra = uuid
+            .into_iter()
+            .chain(extra.iter().cloned())
+            .collect::<Vec<ExtraRow>>();
+        Self::show_rows(dec, hex, bin, &ty.to_bytes(*self), &extra, opts)
+    }
+
+    // Shows the value at the width of the layout when it fits, with a row per field followed by
+    // the extra rows
+    fn show_fields_with(&self, layout: &Layout, extra: &[ExtraRow], opts: &ShowOpts) -> String {
+        let ty = IntType::new(layout.width(), false).filter(|ty| ty.fits(*self));
+        let name_len = layout
+            .fields
+            .iter()
+            .map(|f| f.name.len())
+            .max()
+            .unwrap_or(0);
+        let fields = layout
+            .fields
+            .iter()
+            .map(|field| {
+                let value = field.extract(*self);
+                let label = if field.hi == field.lo {
+                    format!("{:<2$} = {}", field.name, value, name_len)
+                } else {
+                    format!("{:<3$} = {} ({:#x})", field.name, value, value, name_len)
+                };
+                ExtraRow::Field(field.hi, field.lo, label)
+            })
+            .chain(extra.iter().cloned())
+            .collect::<Vec<ExtraRow>>();
+        self.show_extra(ty, &fields, opts)
+    }
+
+    fn show_widths_with(&self, opts: &ShowOpts) -> String {
+        let grouping = &opts.grouping;
+        let rows = [8, 16, 32, 64, 128]
+            .iter()
+            .flat_map(|&bits| vec![IntType::new(bits, false), IntType::new(bits, true)])
+            .flatten()
+            .map(|ty| {
+                let dec = Self::group_with(ty.fmt_dec(*self), grouping.dec).0;
+                let hex = format!("{:01$x}", ty.raw(*self), ty.bits as usize / 4);
+                (ty, dec, Self::group_with(hex, grouping.hex).0)
+            })
+            .collect::<Vec<(IntType, String, String)>>();
+        let dec_len = rows.iter().map(|(_, dec, _)| dec.len()).max().unwrap();
+        let hex_len = rows.iter().map(|(_, _, hex)| hex.len()).max().unwrap();
+        rows.iter()
+            .map(|(ty, dec, hex)| {
+                let (color, marker) = if ty.fits(*self) {
+                    (&opts.theme.dec, '*')
+                } else {
+                    (&opts.theme.ruler, ' ')
+                };
+                let row = format!(
+                    "{} {:<5} {:>4$}  {:>5$} h",
+                    marker,
+                    ty.to_string(),
+                    dec,
+                    hex,
+                    dec_len,
+                    hex_len
+                );
+                opts.paint(color, &row)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
     }
 }
 
@@ -97,6 +508,157 @@ fn test_show() {
         "\u{1b}[36mDec                             3 405 691 582  \u{1b}[0m\n\
          \u{1b}[92mHex                                ca feb abe h\u{1b}[0m\n\
          \u{1b}[91mBin   1100 1010 1111 1110 1011 1010 1011 1110 b\u{1b}[0m\n\
-         \u{1b}[90m      ---28---24---20---16---12----8----4----0 \u{1b}[0m"
+         \u{1b}[90m      ---28---24---20---16---12----8----4----0 \u{1b}[0m\n\
+         \u{1b}[33mLE                                be ba fe ca  \u{1b}[0m\n\
+         \u{1b}[33mBE                                ca fe ba be  \u{1b}[0m"
+    );
+}
+
+#[test]
+fn test_show_typed() {
+    let i: i128 = -2;
+    assert_eq!(
+        i.show_typed(IntType::parse("i8").unwrap()),
+        "\u{1b}[36mDec          -2  \u{1b}[0m\n\
+         \u{1b}[92mHex          fe h\u{1b}[0m\n\
+         \u{1b}[91mBin   1111 1110 b\u{1b}[0m\n\
+         \u{1b}[90m      ----4----0 \u{1b}[0m\n\
+         \u{1b}[33mLE           fe  \u{1b}[0m\n\
+         \u{1b}[33mBE           fe  \u{1b}[0m"
     );
+    assert_eq!(
+        i.show_typed(IntType::parse("u12").unwrap()),
+        "\u{1b}[36mDec            4 094  \u{1b}[0m\n\
+         \u{1b}[92mHex              ffe h\u{1b}[0m\n\
+         \u{1b}[91mBin   1111 1111 1110 b\u{1b}[0m\n\
+         \u{1b}[90m      ----8----4----0 \u{1b}[0m\n\
+         \u{1b}[33mLE             fe 0f  \u{1b}[0m\n\
+         \u{1b}[33mBE             0f fe  \u{1b}[0m"
+    );
+}
+
+#[test]
+fn test_show_widths() {
+    let i: i128 = -2;
+    let widths = i.show_widths();
+    let lines = widths.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 10);
+    assert_eq!(
+        lines[0],
+        "\u{1b}[90m  u8                                                    254                                          fe h\u{1b}[0m"
+    );
+    assert_eq!(
+        lines[1],
+        "\u{1b}[36m* i8                                                     -2                                          fe h\u{1b}[0m"
+    );
+    assert_eq!(
+        lines[8],
+        "\u{1b}[90m  u128  340 282 366 920 938 463 463 374 607 431 768 211 454  ff fff fff fff fff fff fff fff fff fff ffe h\u{1b}[0m"
+    );
+    assert!(lines[9].starts_with("\u{1b}[36m* i128"));
+}
+
+#[test]
+fn test_show_grouping() {
+    let mut opts = ShowOpts::default();
+    opts.grouping.set("hex", "2", None).unwrap();
+    opts.grouping.set("dec", "3", Some(",")).unwrap();
+    opts.grouping.set("bin", "8", Some("_")).unwrap();
+    let i: i128 = -1234567;
+    assert_eq!(
+        i.show_with(IntType::parse("i32"), &opts),
+        "\u{1b}[36mDec                            -1,234,567  \u{1b}[0m\n\
+         \u{1b}[92mHex                           ff ed 29 79 h\u{1b}[0m\n\
+         \u{1b}[91mBin   11111111_11101101_00101001_01111001 b\u{1b}[0m\n\
+         \u{1b}[90m      -------24-------16--------8--------0 \u{1b}[0m\n\
+         \u{1b}[33mLE                            79 29 ed ff  \u{1b}[0m\n\
+         \u{1b}[33mBE                            ff ed 29 79  \u{1b}[0m"
+    );
+    opts.grouping.set("dec", "0", None).unwrap();
+    assert!(i.show_with(None, &opts).contains("Dec   "));
+    assert!(i.show_with(None, &opts).contains(" -1234567  "));
+    assert!(opts.grouping.set("oct", "3", None).is_err());
+    assert!(opts.grouping.set("bin", "2", None).is_err());
+    assert!(opts.grouping.set("hex", "x", None).is_err());
+    assert!(opts.grouping.set("hex", "4", Some("ab")).is_err());
+    let group = Group { size: 3, sep: ' ' };
+    assert_eq!(
+        <i128 as PresentNum>::group_with("-123".to_string(), group).0,
+        "-123"
+    );
+    let mut grouping = Grouping::default();
+    grouping.set("dec", "3", Some(",")).unwrap();
+    grouping.set("hex", "4", Some("_")).unwrap();
+    grouping.set("bin", "8", None).unwrap();
+    assert_eq!(0xcafebabe.as_dec_with(false, &grouping), "3,405,691,582  ");
+    assert_eq!(0xcafebabe.as_hex_with(false, &grouping), "cafe_babe h");
+    assert_eq!(0x1234.as_bin_with(false, &grouping).0, "10010 00110100 b");
+    assert_eq!(0x1234.as_hex_with(true, &grouping), "1234");
+}
+
+#[test]
+fn test_show_color() {
+    let mut opts = ShowOpts {
+        color: false,
+        ..ShowOpts::default()
+    };
+    assert_eq!(
+        0xabi128.show_with(None, &opts),
+        "Dec         171  \n\
+         Hex          ab h\n\
+         Bin   1010 1011 b\n      \
+         ----4----0 \n\
+         LE           ab  \n\
+         BE           ab  "
+    );
+    opts.color = true;
+    opts.theme.set("dec=bright-blue, bytes=1;35").unwrap();
+    let shown = 0xabi128.show_with(None, &opts);
+    assert!(shown.starts_with("\u{1b}[94mDec "));
+    assert!(shown.ends_with("\u{1b}[1;35mBE           ab  \u{1b}[0m"));
+    assert!(opts.theme.set("dec=purple").is_err());
+    assert!(opts.theme.set("oct=red").is_err());
+    assert!(opts.theme.set("hex").is_err());
+    assert_eq!(ColorChoice::parse("never").unwrap(), ColorChoice::Never);
+    assert!(!ColorChoice::Never.enabled());
+    assert!(ColorChoice::Always.enabled());
+    assert!(ColorChoice::parse("sometimes").is_err());
+}
+
+#[test]
+fn test_show_fields() {
+    let layout = Layout::parse("CTRL { EN[0], MODE[3:1], DIV[15:8] }").unwrap();
+    let opts = ShowOpts {
+        color: false,
+        ..ShowOpts::default()
+    };
+    assert_eq!(
+        0x80bi128.show_fields_with(&layout, &[], &opts),
+        "Dec                 2 059  \n\
+         Hex                 0 80b h\n\
+         Bin   0000 1000 0000 1011 b\n      \
+         ---12----8----4----0 \n      \
+         0000 1000            DIV  = 8 (0x8)\n                     \
+         101   MODE = 5 (0x5)\n                        \
+         1  EN   = 1\n\
+         LE                  0b 08  \n\
+         BE                  08 0b  "
+    );
+    let shown = 0x1_0000i128.show_fields(&layout);
+    assert!(shown.contains("Bin   1 0000 0000 0000 0000 b"));
+    assert!(shown.contains("\u{1b}[35m        0000 0000            DIV  = 0 (0x0)\u{1b}[0m"));
+}
+
+#[test]
+fn test_show_uuid() {
+    let uuid = 0x123e_4567_e89b_12d3_a456_4266_1417_4000i128.show_all();
+    assert!(uuid.contains("UUID  123e4567-e89b-12d3-a456-426614174000"));
+    assert!(5i128
+        .show_typed(IntType::parse("u128").unwrap())
+        .contains("UUID  00000000-0000-0000-0000-000000000005"));
+    assert!(!i128::from(u64::MAX).show_all().contains("UUID"));
+    assert!(!(-0x1_0000_0000_0000_0000i128).show_all().contains("UUID"));
+    assert!((-1i128)
+        .show_typed(IntType::parse("i128").unwrap())
+        .contains("UUID"));
 }
@@ -0,0 +1,175 @@
+use crate::error::{Error, Result};
+use crate::reprs::{parse_num, IntType};
+use std::convert::TryFrom;
+
+// The bias K of excess-K, `Half` being the offset binary bias of half the range
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Bias {
+    Half,
+    K(i128),
+}
+
+impl Bias {
+    pub fn parse(s: &str) -> Result<Bias> {
+        if s == "half" {
+            return Ok(Bias::Half);
+        }
+        parse_num(s)
+            .map(Bias::K)
+            .map_err(|_| Error::OptionParseError(s.to_string()))
+    }
+}
+
+fn width(bits: u32) -> Option<(IntType, u128)> {
+    let ty = IntType::new(bits, true)?;
+    Some((ty, 1 << (bits - 1)))
+}
+
+// The largest magnitude of ones' complement and sign-magnitude, which spend a pattern on -0
+fn max_magnitude(bits: u32) -> Option<i128> {
+    let (_, top) = width(bits)?;
+    Some((top - 1) as i128)
+}
+
+// Reads the low `bits` of `x` as ones' complement, where negating flips every bit
+pub fn from_ones(x: i128, bits: u32) -> Option<i128> {
+    let (ty, top) = width(bits)?;
+    let raw = ty.raw(x);
+    if raw & top == 0 {
+        Some(raw as i128)
+    } else {
+        Some(-(ty.raw(!raw as i128) as i128))
+    }
+}
+
+pub fn to_ones(x: i128, bits: u32) -> Option<i128> {
+    let (ty, _) = width(bits)?;
+    if x.checked_abs()? > max_magnitude(bits)? {
+        return None;
+    }
+    Some(ty.raw(if x < 0 { !-x } else { x }) as i128)
+}
+
+// Reads the low `bits` of `x` as a sign bit over a magnitude
+pub fn from_sign_magnitude(x: i128, bits: u32) -> Option<i128> {
+    let (ty, top) = width(bits)?;
+    let raw = ty.raw(x);
+    let magnitude = (raw & !top) as i128;
+    Some(if raw & top == 0 {
+        magnitude
+    } else {
+        -magnitude
+    })
+}
+
+pub fn to_sign_magnitude(x: i128, bits: u32) -> Option<i128> {
+    let (_, top) = width(bits)?;
+    if x.checked_abs()? > max_magnitude(bits)? {
+        return None;
+    }
+    Some(if x < 0 { (top | -x as u128) as i128 } else { x })
+}
+
+// Reads the low `bits` of `x` as excess-K, the value plus K. Offset binary is two's complement
+// with the sign bit flipped
+pub fn from_excess(x: i128, bits: u32, bias: Bias) -> Option<i128> {
+    let (ty, top) = width(bits)?;
+    let raw = ty.raw(x);
+    match bias {
+        Bias::Half => Some(ty.wrap((raw ^ top) as i128)),
+        Bias::K(k) => i128::try_from(raw).ok()?.checked_sub(k),
+    }
+}
+
+pub fn to_excess(x: i128, bits: u32, bias: Bias) -> Option<i128> {
+    let (ty, top) = width(bits)?;
+    let raw = match bias {
+        Bias::Half if ty.fits(x) => ty.raw(x) ^ top,
+        Bias::Half => return None,
+        Bias::K(k) => u128::try_from(x.checked_add(k)?).ok()?,
+    };
+    Some(raw as i128).filter(|_| raw <= ty.raw(-1))
+}
+
+// Shows -0, which ones' complement and sign-magnitude have besides 0
+fn show_signed(value: i128, negative: bool) -> String {
+    if value == 0 && negative {
+        String::from("-0")
+    } else {
+        value.to_string()
+    }
+}
+
+// What the bit pattern of `x` means at the width of `ty` in ones' complement, sign-magnitude
+// and excess-K
+pub fn describe(x: i128, ty: IntType, bias: Bias) -> Vec<(&'static str, String)> {
+    let bits = ty.bits;
+    let negative = ty.raw(x) >> (bits - 1) != 0;
+    let mut rows = Vec::new();
+    if let Some(ones) = from_ones(x, bits) {
+        rows.push(("Ones", show_signed(ones, negative)));
+    }
+    if let Some(sm) = from_sign_magnitude(x, bits) {
+        rows.push(("SgnM", show_signed(sm, negative)));
+    }
+    let k = match bias {
+        Bias::Half => format!("2^{}", bits - 1),
+        Bias::K(k) => k.to_string(),
+    };
+    if let Some(excess) = from_excess(x, bits, bias) {
+        rows.push(("Exc", format!("{} (K={})", excess, k)));
+    }
+    rows
+}
+
+#[test]
+fn test_ones_and_sign_magnitude() {
+    assert_eq!(from_ones(0xfa, 8), Some(-5));
+    assert_eq!(from_ones(0xff, 8), Some(0));
+    assert_eq!(from_ones(0x7f, 8), Some(127));
+    assert_eq!(to_ones(-5, 8), Some(0xfa));
+    assert_eq!(to_ones(-128, 8), None);
+    assert_eq!(to_ones(i128::MIN + 1, 128), Some(1 << 127));
+    assert_eq!(from_ones(1 << 127, 128), Some(i128::MIN + 1));
+    assert_eq!(from_sign_magnitude(0x85, 8), Some(-5));
+    assert_eq!(from_sign_magnitude(0x80, 8), Some(0));
+    assert_eq!(from_sign_magnitude(0x805, 12), Some(-5));
+    assert_eq!(to_sign_magnitude(-5, 12), Some(0x805));
+    assert_eq!(to_sign_magnitude(128, 8), None);
+    assert_eq!(from_sign_magnitude(-1, 128), Some(-i128::MAX));
+    assert_eq!(to_ones(1, 0), None);
+}
+
+#[test]
+fn test_excess() {
+    assert_eq!(from_excess(0x80, 8, Bias::Half), Some(0));
+    assert_eq!(from_excess(0, 8, Bias::Half), Some(-128));
+    assert_eq!(from_excess(0xff, 8, Bias::Half), Some(127));
+    assert_eq!(from_excess(0, 128, Bias::Half), Some(i128::MIN));
+    assert_eq!(to_excess(-1, 12, Bias::Half), Some(0x7ff));
+    assert_eq!(to_excess(128, 8, Bias::Half), None);
+    assert_eq!(from_excess(0x7f, 8, Bias::K(127)), Some(0));
+    assert_eq!(from_excess(0x10, 8, Bias::K(127)), Some(-111));
+    assert_eq!(to_excess(-127, 8, Bias::K(127)), Some(0));
+    assert_eq!(to_excess(129, 8, Bias::K(127)), None);
+    assert_eq!(to_excess(-128, 8, Bias::K(127)), None);
+    assert_eq!(Bias::parse("half").unwrap(), Bias::Half);
+    assert_eq!(Bias::parse("0x7f").unwrap(), Bias::K(127));
+    assert!(Bias::parse("half-ish").is_err());
+}
+
+#[test]
+fn test_describe_signs() {
+    let u8_t = IntType::parse("u8").unwrap();
+    assert_eq!(
+        describe(0xfa, u8_t, Bias::Half),
+        vec![
+            ("Ones", String::from("-5")),
+            ("SgnM", String::from("-122")),
+            ("Exc", String::from("122 (K=2^7)")),
+        ]
+    );
+    assert_eq!(describe(0xff, u8_t, Bias::K(127))[0].1, "-0");
+    assert_eq!(describe(0x80, u8_t, Bias::K(127))[1].1, "-0");
+    assert_eq!(describe(0x80, u8_t, Bias::K(127))[2].1, "1 (K=127)");
+}
@@ -0,0 +1,264 @@
+use crate::error::{Error, Result};
+use crate::reprs::parse_num;
+use std::fmt::Display;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum TableKind {
+    Flags,
+    Enum,
+}
+
+// Named values, either bit flags that are or'ed together or the variants of an enum
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Table {
+    pub name: String,
+    pub kind: TableKind,
+    pub entries: Vec<(String, i128)>,
+}
+
+const OPEN: &[(&str, i128)] = &[
+    ("O_RDONLY", 0),
+    ("O_WRONLY", 0o1),
+    ("O_RDWR", 0o2),
+    ("O_CREAT", 0o100),
+    ("O_EXCL", 0o200),
+    ("O_NOCTTY", 0o400),
+    ("O_TRUNC", 0o1000),
+    ("O_APPEND", 0o2000),
+    ("O_NONBLOCK", 0o4000),
+    ("O_DSYNC", 0o10000),
+    ("O_ASYNC", 0o20000),
+    ("O_DIRECT", 0o40000),
+    ("O_LARGEFILE", 0o100000),
+    ("O_DIRECTORY", 0o200000),
+    ("O_NOFOLLOW", 0o400000),
+    ("O_NOATIME", 0o1000000),
+    ("O_CLOEXEC", 0o2000000),
+    ("O_SYNC", 0o4010000),
+    ("O_PATH", 0o10000000),
+    ("O_TMPFILE", 0o20200000),
+];
+
+const PROT: &[(&str, i128)] = &[
+    ("PROT_NONE", 0),
+    ("PROT_READ", 0x1),
+    ("PROT_WRITE", 0x2),
+    ("PROT_EXEC", 0x4),
+    ("PROT_GROWSDOWN", 0x0100_0000),
+    ("PROT_GROWSUP", 0x0200_0000),
+];
+
+const MAP: &[(&str, i128)] = &[
+    ("MAP_SHARED", 0x1),
+    ("MAP_PRIVATE", 0x2),
+    ("MAP_SHARED_VALIDATE", 0x3),
+    ("MAP_FIXED", 0x10),
+    ("MAP_ANONYMOUS", 0x20),
+    ("MAP_GROWSDOWN", 0x100),
+    ("MAP_DENYWRITE", 0x800),
+    ("MAP_EXECUTABLE", 0x1000),
+    ("MAP_LOCKED", 0x2000),
+    ("MAP_NORESERVE", 0x4000),
+    ("MAP_POPULATE", 0x8000),
+    ("MAP_NONBLOCK", 0x10000),
+    ("MAP_STACK", 0x20000),
+    ("MAP_HUGETLB", 0x40000),
+    ("MAP_SYNC", 0x80000),
+    ("MAP_FIXED_NOREPLACE", 0x10_0000),
+];
+
+const MODE: &[(&str, i128)] = &[
+    ("S_ISUID", 0o4000),
+    ("S_ISGID", 0o2000),
+    ("S_ISVTX", 0o1000),
+    ("S_IRWXU", 0o700),
+    ("S_IRUSR", 0o400),
+    ("S_IWUSR", 0o200),
+    ("S_IXUSR", 0o100),
+    ("S_IRWXG", 0o70),
+    ("S_IRGRP", 0o40),
+    ("S_IWGRP", 0o20),
+    ("S_IXGRP", 0o10),
+    ("S_IRWXO", 0o7),
+    ("S_IROTH", 0o4),
+    ("S_IWOTH", 0o2),
+    ("S_IXOTH", 0o1),
+];
+
+// Flag tables of Linux that are always available
+const BUILTIN_TABLES: &[(&str, &[(&str, i128)])] =
+    &[("OPEN", OPEN), ("PROT", PROT), ("MAP", MAP), ("MODE", MODE)];
+
+pub fn builtin_names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_TABLES.iter().map(|(name, _)| *name)
+}
+
+pub fn builtin_table(name: &str) -> Option<Table> {
+    let (name, entries) = BUILTIN_TABLES.iter().find(|(n, _)| *n == name)?;
+    Some(Table {
+        name: name.to_string(),
+        kind: TableKind::Flags,
+        entries: entries.iter().map(|(n, v)| (n.to_string(), *v)).collect(),
+    })
+}
+
+pub fn builtin_symbol(name: &str) -> Option<i128> {
+    BUILTIN_TABLES
+        .iter()
+        .flat_map(|(_, entries)| entries.iter())
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| *v)
+}
+
+fn unknown(x: i128) -> String {
+    if x < 0 {
+        format!("-{:#x} (unknown)", x.unsigned_abs())
+    } else {
+        format!("{:#x} (unknown)", x)
+    }
+}
+
+impl Table {
+    // Parses a definition written as `NAME { SYMBOL=value, ... }`
+    pub fn parse(kind: TableKind, def: &str) -> Result<Table> {
+        let err = || Error::TableParseError(def.trim().to_string());
+        let open = def.find('{').ok_or_else(err)?;
+        let body = def[open + 1..]
+            .trim_end()
+            .strip_suffix('}')
+            .ok_or_else(err)?;
+        let name = def[..open].trim();
+        let is_name = |name: &str| {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && parse_num(name).is_err()
+        };
+        if !is_name(name) {
+            return Err(err());
+        }
+        let mut entries: Vec<(String, i128)> = Vec::new();
+        for entry in body.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let eq = entry.find('=').ok_or_else(err)?;
+            let symbol = entry[..eq].trim();
+            let value = entry[eq + 1..].trim();
+            let value = match value.strip_prefix('-') {
+                Some(value) => -parse_num(value)?,
+                None => parse_num(value)?,
+            };
+            if !is_name(symbol) || entries.iter().any(|(s, _)| s == symbol) {
+                return Err(Error::TableParseError(symbol.to_string()));
+            }
+            entries.push((symbol.to_string(), value));
+        }
+        Ok(Table {
+            name: name.to_string(),
+            kind,
+            entries,
+        })
+    }
+
+    // Names the value, e.g. `O_WRONLY|O_CREAT|0x8000 (unknown)` for flags
+    pub fn decode(&self, x: i128) -> String {
+        match self.kind {
+            TableKind::Enum => self
+                .entries
+                .iter()
+                .find(|(_, v)| *v == x)
+                .map_or_else(|| unknown(x), |(name, _)| name.clone()),
+            TableKind::Flags => {
+                if x == 0 {
+                    return self
+                        .entries
+                        .iter()
+                        .find(|(_, v)| *v == 0)
+                        .map_or_else(|| String::from("0"), |(name, _)| name.clone());
+                }
+                // flags made of several bits, such as O_SYNC, are taken before their parts
+                let mut order = (0..self.entries.len())
+                    .filter(|&i| self.entries[i].1 != 0)
+                    .collect::<Vec<usize>>();
+                order.sort_by_key(|&i| std::cmp::Reverse(self.entries[i].1.count_ones()));
+                let mut rest = x;
+                let mut matched = vec![false; self.entries.len()];
+                for i in order {
+                    let v = self.entries[i].1;
+                    if rest & v == v {
+                        rest &= !v;
+                        matched[i] = true;
+                    }
+                }
+                let mut names = self
+                    .entries
+                    .iter()
+                    .zip(matched)
+                    .filter(|(_, matched)| *matched)
+                    .map(|((name, _), _)| name.clone())
+                    .collect::<Vec<String>>();
+                if rest != 0 {
+                    names.push(unknown(rest));
+                }
+                names.join("|")
+            }
+        }
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            TableKind::Flags => "flags",
+            TableKind::Enum => "enum",
+        };
+        let entries = self
+            .entries
+            .iter()
+            .map(|(name, v)| format!("{}={:#x}", name, v))
+            .collect::<Vec<String>>();
+        write!(f, "{} {} {{ {} }}", kind, self.name, entries.join(", "))
+    }
+}
+
+#[test]
+fn test_table_flags() {
+    let open = Table::parse(
+        TableKind::Flags,
+        "OPEN { O_WRONLY=1, O_CREAT=0x40, O_TRUNC=0x200 }",
+    )
+    .unwrap();
+    assert_eq!(open.decode(0x8041), "O_WRONLY|O_CREAT|0x8000 (unknown)");
+    assert_eq!(open.decode(0x241), "O_WRONLY|O_CREAT|O_TRUNC");
+    assert_eq!(open.decode(0), "0");
+    assert_eq!(
+        open.to_string(),
+        "flags OPEN { O_WRONLY=0x1, O_CREAT=0x40, O_TRUNC=0x200 }"
+    );
+    let open = builtin_table("OPEN").unwrap();
+    assert_eq!(open.decode(0), "O_RDONLY");
+    assert_eq!(open.decode(0o4010002), "O_RDWR|O_SYNC");
+    assert_eq!(open.decode(0o10000), "O_DSYNC");
+    assert_eq!(
+        builtin_table("MODE").unwrap().decode(0o4755),
+        "S_ISUID|S_IRWXU|S_IRGRP|S_IXGRP|S_IROTH|S_IXOTH"
+    );
+    assert_eq!(
+        builtin_table("MAP").unwrap().decode(0x22),
+        "MAP_PRIVATE|MAP_ANONYMOUS"
+    );
+    assert_eq!(builtin_symbol("PROT_EXEC"), Some(4));
+    assert_eq!(builtin_symbol("PROT"), None);
+}
+
+#[test]
+fn test_table_enum() {
+    let state = Table::parse(TableKind::Enum, "STATE { IDLE=0, RUN=1, ERR=-1 }").unwrap();
+    assert_eq!(state.decode(1), "RUN");
+    assert_eq!(state.decode(-1), "ERR");
+    assert_eq!(state.decode(7), "0x7 (unknown)");
+    assert_eq!(state.decode(-7), "-0x7 (unknown)");
+    assert!(Table::parse(TableKind::Enum, "STATE { IDLE }").is_err());
+    assert!(Table::parse(TableKind::Enum, "STATE { IDLE=0, IDLE=1 }").is_err());
+    assert!(Table::parse(TableKind::Enum, "STATE { IDLE=x }").is_err());
+    assert!(Table::parse(TableKind::Enum, "ABC { IDLE=0 }").is_err());
+    assert!(Table::parse(TableKind::Enum, "STATE IDLE=0").is_err());
+}
@@ -0,0 +1,290 @@
+use crate::error::{Error, Result};
+use crate::reprs::IntType;
+use std::convert::TryFrom;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Radix {
+    Dec,
+    Hex,
+    HexUpper,
+    Bin,
+    Oct,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+struct Field {
+    radix: Radix,
+    // negative values are written as a minus sign and their magnitude
+    signed: bool,
+    prefix: bool,
+    zero_pad: bool,
+    width: usize,
+    sep: Option<(char, usize)>,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+enum Piece {
+    Text(String),
+    Field(Field),
+    // A C integer constant, which must fit in `long long` or `unsigned long long`
+    CLiteral,
+}
+
+// An output template such as `0x{hex:016} ({dec})`, where each field is written as
+// `{radix[:[-][#][0][width][sep every]]}`
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+const PRESETS: [(&str, &str); 2] = [("rust", "{hex:-#_4}"), ("python", "{hex:-#_4}")];
+
+// `0x..ULL`, or `(-0x..LL)` for negative values, read at the width of `ty` when it has one
+fn c_literal(x: i128, ty: Option<IntType>) -> Result<String> {
+    if let Some(ty) = ty.filter(|ty| !ty.signed) {
+        return match u64::try_from(ty.raw(x)) {
+            Ok(raw) => Ok(format!("0x{:x}ULL", raw)),
+            Err(_) => Err(Error::FormatRangeError(ty.raw(x).to_string())),
+        };
+    }
+    let x = ty.map_or(x, |ty| ty.wrap(x));
+    match x {
+        // the magnitude of `i64::MIN` doesn't fit in `long long`
+        x if x == i128::from(i64::MIN) => Ok(String::from("(-0x7fffffffffffffffLL - 1)")),
+        x if x < 0 && x > i128::from(i64::MIN) => Ok(format!("(-0x{:x}LL)", -x)),
+        x if (0..=i128::from(u64::MAX)).contains(&x) => Ok(format!("0x{:x}ULL", x)),
+        x => Err(Error::FormatRangeError(x.to_string())),
+    }
+}
+
+impl Radix {
+    // One of `dec`, `hex`, `HEX`, `bin` or `oct`
+    pub fn parse(name: &str) -> Option<Radix> {
+        match name.trim() {
+            "dec" => Some(Radix::Dec),
+            "hex" => Some(Radix::Hex),
+            "HEX" => Some(Radix::HexUpper),
+            "bin" => Some(Radix::Bin),
+            "oct" => Some(Radix::Oct),
+            _ => None,
+        }
+    }
+}
+
+impl Field {
+    fn parse(field: &str) -> Result<Field> {
+        let err = || Error::FormatParseError(field.to_string());
+        let (name, spec) = match field.find(':') {
+            Some(i) => (&field[..i], &field[i + 1..]),
+            None => (field, ""),
+        };
+        let radix = Radix::parse(name).ok_or_else(err)?;
+        let mut spec = spec.trim();
+        let signed = spec.starts_with('-');
+        if signed {
+            spec = &spec[1..];
+        }
+        let prefix = spec.starts_with('#');
+        if prefix {
+            spec = &spec[1..];
+        }
+        let zero_pad = spec.starts_with('0');
+        let width_end = spec
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(spec.len());
+        let width = if width_end > 0 {
+            spec[..width_end].parse().map_err(|_| err())?
+        } else {
+            0
+        };
+        spec = &spec[width_end..];
+        let sep = match spec.chars().next() {
+            Some(c) if "_',".contains(c) => {
+                let every = spec[1..].parse().map_err(|_| err())?;
+                if every == 0 {
+                    return Err(err());
+                }
+                Some((c, every))
+            }
+            Some(_) => return Err(err()),
+            None => None,
+        };
+        Ok(Field {
+            radix,
+            signed,
+            prefix,
+            zero_pad,
+            width,
+            sep,
+        })
+    }
+
+    fn render(&self, x: i128, ty: Option<IntType>) -> String {
+        let mut raw = ty.map_or(x as u128, |ty| ty.raw(x));
+        let mut sign = "";
+        if self.signed && ty.is_none_or(|ty| ty.signed) {
+            let x = ty.map_or(x, |ty| ty.wrap(x));
+            if x < 0 {
+                sign = "-";
+                raw = x.unsigned_abs();
+            }
+        }
+        let (sign, mut digits, prefix) = match self.radix {
+            Radix::Dec => {
+                let dec = ty.map_or_else(|| x.to_string(), |ty| ty.fmt_dec(x));
+                match dec.strip_prefix('-') {
+                    Some(dec) => ("-", dec.to_string(), ""),
+                    None => ("", dec, ""),
+                }
+            }
+            Radix::Hex => (sign, format!("{:x}", raw), "0x"),
+            Radix::HexUpper => (sign, format!("{:X}", raw), "0x"),
+            Radix::Bin => (sign, format!("{:b}", raw), "0b"),
+            Radix::Oct => (sign, format!("{:o}", raw), "0o"),
+        };
+        let prefix = if self.prefix { prefix } else { "" };
+        // the width takes in the sign and prefix, as in Rust's `{:#010x}`
+        let pad_width = self.width.saturating_sub(sign.len() + prefix.len());
+        if self.zero_pad && digits.len() < pad_width {
+            digits.insert_str(0, &"0".repeat(pad_width - digits.len()));
+        }
+        if let Some((sep, every)) = self.sep {
+            let mut grouped = Vec::new();
+            for (i, c) in digits.chars().rev().enumerate() {
+                if i > 0 && i % every == 0 {
+                    grouped.push(sep);
+                }
+                grouped.push(c);
+            }
+            digits = grouped.iter().rev().collect();
+        }
+        format!(
+            "{:>1$}",
+            format!("{}{}{}", sign, prefix, digits),
+            self.width
+        )
+    }
+}
+
+impl Template {
+    // Parses a template, or one of the `c`, `rust` or `python` presets
+    pub fn parse(template: &str) -> Result<Template> {
+        if template == "c" {
+            return Ok(Template {
+                pieces: vec![Piece::CLiteral],
+            });
+        }
+        if let Some((_, preset)) = PRESETS.iter().find(|(name, _)| *name == template) {
+            return Self::parse(preset);
+        }
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut rest = template;
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                text.push(c);
+                rest = &rest[2..];
+            } else if c == '{' {
+                let end = rest
+                    .find('}')
+                    .ok_or_else(|| Error::FormatParseError(rest.to_string()))?;
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(text.split_off(0)));
+                }
+                pieces.push(Piece::Field(Field::parse(&rest[1..end])?));
+                rest = &rest[end + 1..];
+            } else if c == '}' {
+                return Err(Error::FormatParseError(rest.to_string()));
+            } else {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Template { pieces })
+    }
+
+    pub fn render(&self, x: i128, ty: Option<IntType>) -> Result<String> {
+        self.pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => Ok(text.clone()),
+                Piece::Field(field) => Ok(field.render(x, ty)),
+                Piece::CLiteral => c_literal(x, ty),
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_template() {
+    let render = |template, x| Template::parse(template).unwrap().render(x, None).unwrap();
+    assert_eq!(
+        render("0x{hex:016} ({dec}) [{bin:32}]", 0x1234),
+        "0x0000000000001234 (4660) [                   1001000110100]"
+    );
+    assert_eq!(render("{HEX:#}", 0xcafe), "0xCAFE");
+    assert_eq!(render("{bin:#010'4}", 0x12), "0b0001'0010");
+    assert_eq!(render("{dec:_3}", -1234567), "-1_234_567");
+    assert_eq!(
+        render("{dec:6}|{oct:#}", -12),
+        format!("   -12|0o{:o}", -12i128)
+    );
+    assert_eq!(render("{{{hex}}}", 255), "{ff}");
+    assert_eq!(render("c", 0x1234), "0x1234ULL");
+    assert_eq!(render("rust", 0xdeadbeef), "0xdead_beef");
+    assert_eq!(render("rust", -1), "-0x1");
+    assert_eq!(render("python", -5), "-0x5");
+    assert_eq!(render("python", -0x12345), "-0x1_2345");
+    assert_eq!(render("{dec:06}", -12), "-00012");
+    assert_eq!(render("{hex:#010}", 255), "0x000000ff");
+    assert_eq!(render("{hex:-#06}", -255), "-0x0ff");
+}
+
+#[test]
+fn test_template_typed() {
+    let template = Template::parse("{dec} {hex:#06} {bin:#}").unwrap();
+    assert_eq!(
+        template.render(-1, IntType::parse("i8")).unwrap(),
+        "-1 0x00ff 0b11111111"
+    );
+    assert_eq!(
+        template.render(-1, IntType::parse("u8")).unwrap(),
+        "255 0x00ff 0b11111111"
+    );
+    let rust = Template::parse("rust").unwrap();
+    assert_eq!(rust.render(-1, IntType::parse("i8")).unwrap(), "-0x1");
+    assert_eq!(rust.render(-1, IntType::parse("u8")).unwrap(), "0xff");
+}
+
+#[test]
+fn test_template_c_preset() {
+    let c = Template::parse("c").unwrap();
+    assert_eq!(c.render(0x1234, None).unwrap(), "0x1234ULL");
+    assert_eq!(c.render(-1, None).unwrap(), "(-0x1LL)");
+    assert_eq!(c.render(-1, IntType::parse("i8")).unwrap(), "(-0x1LL)");
+    assert_eq!(c.render(-1, IntType::parse("u8")).unwrap(), "0xffULL");
+    assert_eq!(
+        c.render(i128::from(u64::MAX), None).unwrap(),
+        "0xffffffffffffffffULL"
+    );
+    assert_eq!(
+        c.render(i128::from(i64::MIN), None).unwrap(),
+        "(-0x7fffffffffffffffLL - 1)"
+    );
+    assert!(c.render(1 << 64, None).is_err());
+    assert!(c.render(-1, IntType::parse("u128")).is_err());
+    assert!(c.render(i128::from(i64::MIN) - 1, None).is_err());
+}
+
+#[test]
+fn test_template_errs() {
+    assert!(Template::parse("{hex").is_err());
+    assert!(Template::parse("hex}").is_err());
+    assert!(Template::parse("{nope}").is_err());
+    assert!(Template::parse("{hex:4x}").is_err());
+    assert!(Template::parse("{hex:_0}").is_err());
+    assert!(Template::parse("{hex:_}").is_err());
+}
@@ -0,0 +1,266 @@
+use crate::error::{Error, Result};
+
+// The unit a value is counted in when read as a time
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Unit {
+    // picks the unit that puts the value at a plausible date
+    Auto,
+    Secs,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+const UNITS: [(Unit, &str); 4] = [
+    (Unit::Secs, "s"),
+    (Unit::Millis, "ms"),
+    (Unit::Micros, "us"),
+    (Unit::Nanos, "ns"),
+];
+
+impl Unit {
+    pub fn parse(name: &str) -> Result<Unit> {
+        if name == "auto" {
+            return Ok(Unit::Auto);
+        }
+        UNITS
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(unit, _)| *unit)
+            .ok_or_else(|| Error::OptionParseError(name.to_string()))
+    }
+
+    fn name(self) -> &'static str {
+        UNITS
+            .iter()
+            .find(|(unit, _)| *unit == self)
+            .map_or("auto", |(_, name)| name)
+    }
+
+    fn per_sec(self) -> i128 {
+        match self {
+            Unit::Auto | Unit::Secs => 1,
+            Unit::Millis => 1_000,
+            Unit::Micros => 1_000_000,
+            Unit::Nanos => 1_000_000_000,
+        }
+    }
+
+    // The coarsest unit, trying seconds first and nanoseconds last, in which the value is less
+    // than 1e11 seconds, around the year 5000
+    fn guess(x: i128) -> Unit {
+        UNITS
+            .iter()
+            .map(|(unit, _)| *unit)
+            .find(|unit| x.unsigned_abs() / (unit.per_sec() as u128) < 100_000_000_000)
+            .unwrap_or(Unit::Nanos)
+    }
+}
+
+// Days since 1970-01-01 of a date in the proleptic Gregorian calendar
+fn days_from_civil(y: i128, m: i128, d: i128) -> i128 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i128) -> (i128, i128, i128) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i128::from(m <= 2), m, d)
+}
+
+fn days_in_month(y: i128, m: i128) -> i128 {
+    days_from_civil(y + i128::from(m == 12), m % 12 + 1, 1) - days_from_civil(y, m, 1)
+}
+
+// Reads fixed-width decimal fields such as `2026` or `07` from the front of `s`
+fn take(s: &mut &str, len: usize) -> Option<i128> {
+    let field = s
+        .get(..len)
+        .filter(|f| f.chars().all(|c| c.is_ascii_digit()))?;
+    *s = &s[len..];
+    field.parse().ok()
+}
+
+fn expect(s: &mut &str, c: char) -> Option<()> {
+    *s = s.strip_prefix(c)?;
+    Some(())
+}
+
+// Reads `2026-10-18`, `2026-10-18T12:00:00Z` or `2026-10-18T14:00:00.5+02:00` as Unix seconds,
+// dropping any fraction of a second
+pub fn parse_iso(s: &str) -> Option<i128> {
+    let mut s = s;
+    let y = take(&mut s, 4)?;
+    expect(&mut s, '-')?;
+    let m = take(&mut s, 2).filter(|m| (1..=12).contains(m))?;
+    expect(&mut s, '-')?;
+    let d = take(&mut s, 2).filter(|&d| d >= 1 && d <= days_in_month(y, m))?;
+    let mut secs = days_from_civil(y, m, d) * 86_400;
+    if s.is_empty() {
+        return Some(secs);
+    }
+    s = s.strip_prefix(['T', 't', ' '])?;
+    let h = take(&mut s, 2).filter(|&h| h < 24)?;
+    expect(&mut s, ':')?;
+    let min = take(&mut s, 2).filter(|&min| min < 60)?;
+    let sec = match s.strip_prefix(':') {
+        Some(rest) => {
+            s = rest;
+            take(&mut s, 2).filter(|&sec| sec <= 60)?
+        }
+        None => 0,
+    };
+    if let Some(rest) = s.strip_prefix('.') {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        s = &rest[digits..];
+    }
+    secs += h * 3600 + min * 60 + sec;
+    match s {
+        "" | "Z" | "z" => Some(secs),
+        _ => {
+            let sign = match s.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            s = &s[1..];
+            let off_h = take(&mut s, 2).filter(|&h| h < 24)?;
+            s = s.strip_prefix(':').unwrap_or(s);
+            let off_m = take(&mut s, 2).filter(|&m| m < 60)?;
+            Some(secs - sign * (off_h * 3600 + off_m * 60)).filter(|_| s.is_empty())
+        }
+    }
+}
+
+// Writes the time as UTC, like `2026-10-18T12:00:00Z` or `2026-10-18T12:00:00.250Z`, for
+// years 0 to 9999
+pub fn show_datetime(x: i128, unit: Unit) -> Option<String> {
+    let per_sec = unit.per_sec();
+    let secs = x.div_euclid(per_sec);
+    let frac = x.rem_euclid(per_sec);
+    let (y, m, d) = civil_from_days(secs.div_euclid(86_400));
+    if !(0..=9999).contains(&y) {
+        return None;
+    }
+    let time = secs.rem_euclid(86_400);
+    let mut s = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        y,
+        m,
+        d,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    );
+    if frac != 0 {
+        let width = per_sec.to_string().len() - 1;
+        s.push_str(&format!(".{:01$}", frac, width));
+    }
+    s.push('Z');
+    Some(s)
+}
+
+// Writes a duration like `1d 3h 12m`, with the fraction of a second in finer units
+pub fn show_duration(x: i128, unit: Unit) -> String {
+    let per_sec = unit.per_sec();
+    let abs = x.unsigned_abs();
+    let secs = abs / per_sec as u128;
+    let frac = abs % per_sec as u128;
+    let mut parts = [
+        (secs / 86_400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n != 0)
+    .map(|(n, suffix)| format!("{}{}", n, suffix))
+    .collect::<Vec<String>>();
+    if frac != 0 {
+        let width = per_sec.to_string().len() - 1;
+        let frac = format!("{:01$}", frac, width);
+        parts.push(format!("{}.{}s", secs % 60, frac.trim_end_matches('0')));
+    } else if !secs.is_multiple_of(60) || parts.is_empty() {
+        parts.push(format!("{}s", secs % 60));
+    }
+    let sign = if x < 0 { "-" } else { "" };
+    format!("{}{}", sign, parts.join(" "))
+}
+
+// The value read as a UTC time and as a duration
+pub fn describe(x: i128, unit: Unit) -> Vec<(&'static str, String)> {
+    let unit = match unit {
+        Unit::Auto => Unit::guess(x),
+        unit => unit,
+    };
+    let mut rows = Vec::new();
+    if let Some(datetime) = show_datetime(x, unit) {
+        rows.push(("Time", format!("{} ({})", datetime, unit.name())));
+    }
+    rows.push(("Dur", show_duration(x, unit)));
+    rows
+}
+
+#[test]
+fn test_parse_iso() {
+    assert_eq!(parse_iso("1970-01-01"), Some(0));
+    assert_eq!(parse_iso("2026-10-18T12:00:00Z"), Some(1_792_324_800));
+    assert_eq!(parse_iso("2026-10-18T14:00:00+02:00"), Some(1_792_324_800));
+    assert_eq!(parse_iso("2026-10-18t07:30-0430"), Some(1_792_324_800));
+    assert_eq!(parse_iso("2026-10-18T12:00:00.999Z"), Some(1_792_324_800));
+    assert_eq!(parse_iso("1969-12-31T23:59:59Z"), Some(-1));
+    assert_eq!(parse_iso("2000-02-29"), Some(951_782_400));
+    assert_eq!(parse_iso("1900-02-29"), None);
+    assert_eq!(parse_iso("2026-13-01"), None);
+    assert_eq!(parse_iso("2026-10-18T24:00"), None);
+    assert_eq!(parse_iso("2026-10-18T12:00:00+2"), None);
+    assert_eq!(parse_iso("2026-10-18T12:00:00Zx"), None);
+    assert_eq!(parse_iso("26-10-18"), None);
+}
+
+#[test]
+fn test_show_time() {
+    assert_eq!(
+        show_datetime(1_792_324_800, Unit::Secs).unwrap(),
+        "2026-10-18T12:00:00Z"
+    );
+    assert_eq!(
+        show_datetime(1_792_324_800_250, Unit::Millis).unwrap(),
+        "2026-10-18T12:00:00.250Z"
+    );
+    assert_eq!(
+        show_datetime(-1, Unit::Secs).unwrap(),
+        "1969-12-31T23:59:59Z"
+    );
+    assert_eq!(show_datetime(1 << 40, Unit::Secs), None);
+    assert_eq!(show_duration(98_000, Unit::Secs), "1d 3h 13m 20s");
+    assert_eq!(show_duration(97_920, Unit::Secs), "1d 3h 12m");
+    assert_eq!(show_duration(-1_500, Unit::Millis), "-1.5s");
+    assert_eq!(show_duration(0, Unit::Nanos), "0s");
+    assert_eq!(show_duration(60_000_001, Unit::Micros), "1m 0.000001s");
+    assert_eq!(
+        describe(1_792_324_800_000_000, Unit::Auto),
+        vec![
+            ("Time", String::from("2026-10-18T12:00:00Z (us)")),
+            ("Dur", String::from("20744d 12h")),
+        ]
+    );
+    assert_eq!(describe(90, Unit::Auto)[1], ("Dur", String::from("1m 30s")));
+    assert_eq!(Unit::parse("ms").unwrap(), Unit::Millis);
+    assert!(Unit::parse("min").is_err());
+}
@@ -0,0 +1,63 @@
+use std::convert::TryFrom;
+
+pub fn scalar(x: i128) -> Option<char> {
+    char::from_u32(u32::try_from(x).ok()?)
+}
+
+pub fn utf8(c: char) -> Vec<u8> {
+    c.to_string().into_bytes()
+}
+
+// Reads the code points of UTF-8 bytes
+pub fn from_utf8(bytes: &[u8]) -> Option<Vec<i128>> {
+    let s = std::str::from_utf8(bytes).ok()?;
+    Some(s.chars().map(|c| i128::from(u32::from(c))).collect())
+}
+
+// The value as a Unicode scalar with its UTF-8 and UTF-16 encodings, if it is one. Control
+// and other invisible characters are escaped rather than printed
+pub fn describe(x: i128) -> Vec<(&'static str, String)> {
+    let c = match scalar(x) {
+        Some(c) => c,
+        None => return Vec::new(),
+    };
+    let shown = if c.is_control() || c.is_whitespace() {
+        c.escape_debug().collect::<String>()
+    } else {
+        c.to_string()
+    };
+    let units = c
+        .encode_utf16(&mut [0; 2])
+        .iter()
+        .map(|unit| format!("{:04x}", unit))
+        .collect::<Vec<String>>();
+    let bytes = utf8(c)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<String>>();
+    vec![
+        ("Char", format!("U+{:04X} {}", u32::from(c), shown)),
+        ("UTF8", bytes.join(" ")),
+        ("UTF16", units.join(" ")),
+    ]
+}
+
+#[test]
+fn test_unicode() {
+    assert_eq!(
+        describe(0x1f600),
+        vec![
+            ("Char", String::from("U+1F600 😀")),
+            ("UTF8", String::from("f0 9f 98 80")),
+            ("UTF16", String::from("d83d de00")),
+        ]
+    );
+    assert_eq!(describe(0x41)[0], ("Char", String::from("U+0041 A")));
+    assert_eq!(describe(0xa)[0], ("Char", String::from("U+000A \\n")));
+    assert_eq!(describe(0xe9)[1], ("UTF8", String::from("c3 a9")));
+    assert_eq!(describe(0xd800), vec![]);
+    assert_eq!(describe(0x110000), vec![]);
+    assert_eq!(describe(-1), vec![]);
+    assert_eq!(from_utf8("é😀".as_bytes()), Some(vec![0xe9, 0x1f600]));
+    assert_eq!(from_utf8(&[0xff]), None);
+}
@@ -0,0 +1,69 @@
+// Length of `123e4567-e89b-12d3-a456-426614174000`
+pub const LEN: usize = 36;
+
+// Reads a UUID written as 8-4-4-4-12 hex digits
+pub fn parse(s: &str) -> Option<i128> {
+    let groups = s.split('-').collect::<Vec<&str>>();
+    let lens = groups.iter().map(|g| g.len()).collect::<Vec<usize>>();
+    if lens != [8, 4, 4, 4, 12]
+        || !groups
+            .iter()
+            .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+    u128::from_str_radix(&groups.concat(), 16)
+        .ok()
+        .map(|x| x as i128)
+}
+
+pub fn show(x: i128) -> String {
+    let hex = format!("{:032x}", x as u128);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+// The version in the high nibble of the `time_hi_and_version` field
+pub fn version(x: i128) -> i128 {
+    (x >> 76) & 0xf
+}
+
+// The variant bits at the top of the `clock_seq` field, with the name of the layout they select
+pub fn variant(x: i128) -> (i128, &'static str) {
+    let bits = (x >> 61) & 0b111;
+    match bits {
+        0b000..=0b011 => (0, "NCS"),
+        0b100..=0b101 => (0b10, "RFC 9562"),
+        0b110 => (0b110, "Microsoft"),
+        _ => (0b111, "reserved"),
+    }
+}
+
+#[test]
+fn test_uuid() {
+    let x = parse("123e4567-e89b-12d3-a456-426614174000").unwrap();
+    assert_eq!(x, 0x123e_4567_e89b_12d3_a456_4266_1417_4000);
+    assert_eq!(show(x), "123e4567-e89b-12d3-a456-426614174000");
+    assert_eq!(version(x), 1);
+    assert_eq!(variant(x), (0b10, "RFC 9562"));
+    let x = parse("C232AB00-9414-11EC-B3C8-9F6BDECED846").unwrap();
+    assert_eq!(show(x), "c232ab00-9414-11ec-b3c8-9f6bdeced846");
+    let v4 = parse("919108f7-52d1-4320-9bac-f847db4148a8").unwrap();
+    assert_eq!(version(v4), 4);
+    assert_eq!(variant(v4).1, "RFC 9562");
+    assert_eq!(
+        variant(parse("00000000-0000-0000-c000-000000000000").unwrap()).1,
+        "Microsoft"
+    );
+    assert_eq!(variant(0).1, "NCS");
+    assert_eq!(show(-1), "ffffffff-ffff-ffff-ffff-ffffffffffff");
+    assert_eq!(parse("123e4567-e89b-12d3-a456-42661417400"), None);
+    assert_eq!(parse("123e4567e89b-12d3-a456-426614174000-"), None);
+    assert_eq!(parse("123e4567-e89b-12d3-a456-42661417400g"), None);
+}
@@ -0,0 +1,97 @@
+use crate::error::{Error, Result};
+use std::convert::TryFrom;
+
+// Virtual address formats with 4 KiB pages and four levels of page tables
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+}
+
+impl Arch {
+    pub fn parse(name: &str) -> Result<Arch> {
+        match name {
+            "x86_64" | "x86-64" | "amd64" => Ok(Arch::X86_64),
+            "aarch64" | "arm64" => Ok(Arch::Aarch64),
+            _ => Err(Error::OptionParseError(name.to_string())),
+        }
+    }
+
+    fn levels(self) -> [&'static str; 4] {
+        match self {
+            Arch::X86_64 => ["PML4", "PDPT", "PD", "PT"],
+            Arch::Aarch64 => ["L0", "L1", "L2", "L3"],
+        }
+    }
+
+    // The 9-bit table indices from the top level down, and the offset in the page
+    pub fn split(self, addr: u64) -> ([u64; 4], u64) {
+        let index = |level: u64| (addr >> (39 - 9 * level)) & 0x1ff;
+        ([index(0), index(1), index(2), index(3)], addr & 0xfff)
+    }
+
+    // x86-64 sign-extends bit 47, while AArch64 selects TTBR0 or TTBR1 by bits 63:48
+    pub fn is_canonical(self, addr: u64) -> bool {
+        match self {
+            Arch::X86_64 => ((addr as i64) << 16 >> 16) as u64 == addr,
+            Arch::Aarch64 => matches!(addr >> 48, 0 | 0xffff),
+        }
+    }
+
+    // Describes an address like `PML4=511 PDPT=510 PD=0 PT=0 off=0x10`
+    pub fn describe(self, addr: u64) -> String {
+        let (indices, offset) = self.split(addr);
+        let mut parts = self
+            .levels()
+            .iter()
+            .zip(indices.iter())
+            .map(|(level, index)| format!("{}={}", level, index))
+            .collect::<Vec<String>>();
+        parts.push(format!("off={:#x}", offset));
+        if !self.is_canonical(addr) {
+            parts.push(String::from("(non-canonical)"));
+        }
+        parts.join(" ")
+    }
+}
+
+// Reads a value as a 64-bit address, negative values being taken as two's complement
+pub fn address(x: i128) -> Option<u64> {
+    u64::try_from(x)
+        .ok()
+        .or_else(|| i64::try_from(x).ok().map(|x| x as u64))
+}
+
+#[test]
+fn test_vaddr_x86_64() {
+    let arch = Arch::X86_64;
+    assert_eq!(arch.split(0xffff_ffff_8100_0010), ([511, 510, 8, 0], 0x10));
+    assert_eq!(
+        arch.describe(0xffff_ffff_8100_0010),
+        "PML4=511 PDPT=510 PD=8 PT=0 off=0x10"
+    );
+    assert_eq!(arch.split(0x7fff_ffff_f123), ([255, 511, 511, 511], 0x123));
+    assert!(arch.is_canonical(0x0000_7fff_ffff_ffff));
+    assert!(arch.is_canonical(0xffff_8000_0000_0000));
+    assert!(!arch.is_canonical(0x0000_8000_0000_0000));
+    assert_eq!(
+        arch.describe(0x0000_8000_0000_0000),
+        "PML4=256 PDPT=0 PD=0 PT=0 off=0x0 (non-canonical)"
+    );
+    assert_eq!(Arch::parse("amd64").unwrap(), Arch::X86_64);
+    assert!(Arch::parse("riscv").is_err());
+}
+
+#[test]
+fn test_vaddr_aarch64() {
+    let arch = Arch::Aarch64;
+    assert_eq!(
+        arch.describe(0xffff_8000_1234_5678),
+        "L0=256 L1=0 L2=145 L3=325 off=0x678"
+    );
+    // bit 47 is part of the index, so this is a valid TTBR0 address unlike on x86-64
+    assert!(arch.is_canonical(0x0000_8000_0000_0000));
+    assert!(!arch.is_canonical(0x00ff_0000_0000_0000));
+    assert_eq!(address(-1), Some(u64::MAX));
+    assert_eq!(address(1 << 64), None);
+}
@@ -0,0 +1,187 @@
+use std::convert::TryFrom;
+
+// Unsigned LEB128, 7 bits per byte from the lowest up with the top bit marking that more follow
+pub fn uleb128(x: u128) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut rest = x;
+    loop {
+        let byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+        if rest == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+pub fn sleb128(x: i128) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut rest = x;
+    loop {
+        let byte = (rest & 0x7f) as u8;
+        rest >>= 7;
+        // done once the rest is all sign bits and the sign bit of this byte agrees
+        if (rest == 0 && byte & 0x40 == 0) || (rest == -1 && byte & 0x40 != 0) {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+// The 7-bit groups of a LEB128 value, which must take all of `bytes`, with the last byte and the
+// number of bits they hold. Bits beyond 128 are dropped, for the callers to check
+fn leb128_groups(bytes: &[u8]) -> Option<(u128, u8, u32)> {
+    let (last, init) = bytes.split_last()?;
+    if last & 0x80 != 0 || init.iter().any(|b| b & 0x80 == 0) || bytes.len() > 19 {
+        return None;
+    }
+    let x = bytes
+        .iter()
+        .enumerate()
+        .fold(0, |x, (i, b)| x | u128::from(b & 0x7f) << (7 * i as u32));
+    Some((x, *last, 7 * bytes.len() as u32))
+}
+
+pub fn from_uleb128(bytes: &[u8]) -> Option<u128> {
+    let (x, last, bits) = leb128_groups(bytes)?;
+    // the 19th byte only has room for the top 2 bits
+    Some(x).filter(|_| bits < 133 || last <= 0b11)
+}
+
+pub fn from_sleb128(bytes: &[u8]) -> Option<i128> {
+    let (x, last, bits) = leb128_groups(bytes)?;
+    if bits < 128 {
+        Some(((x << (128 - bits)) as i128) >> (128 - bits))
+    } else {
+        // the bits beyond 128 of the 19th byte must all copy the sign bit
+        Some(x as i128).filter(|_| matches!(last, 0 | 1 | 0x7e | 0x7f))
+    }
+}
+
+// Protobuf varints are unsigned LEB128, negative `int64` values taking all 10 bytes
+pub fn varint(x: i128) -> Option<Vec<u8>> {
+    match u128::try_from(x) {
+        Ok(x) => Some(uleb128(x)),
+        Err(_) => i64::try_from(x).ok().map(|x| uleb128(u128::from(x as u64))),
+    }
+}
+
+// Reads at most 10 bytes of 64 bits, taking those with all 10 bytes as a negative `int64` the
+// way `varint` writes them. A `uint64` of 2^63 or more reads as negative too
+pub fn from_varint(bytes: &[u8]) -> Option<i128> {
+    let x = u64::try_from(from_uleb128(bytes)?).ok()?;
+    if bytes.len() == 10 {
+        Some(i128::from(x as i64))
+    } else {
+        Some(i128::from(x))
+    }
+}
+
+// Maps signed values to unsigned ones as 0, -1, 1, -2, 2... becoming 0, 1, 2, 3, 4...
+pub fn zigzag(x: i128) -> u128 {
+    ((x << 1) ^ (x >> 127)) as u128
+}
+
+pub fn from_zigzag(x: u128) -> i128 {
+    (x >> 1) as i128 ^ -((x & 1) as i128)
+}
+
+// Packed BCD, a decimal digit per nibble
+pub fn bcd(x: u128) -> Option<u128> {
+    x.to_string().chars().try_fold(0u128, |bcd, digit| {
+        bcd.checked_mul(16)?.checked_add(digit.to_digit(10)?.into())
+    })
+}
+
+pub fn from_bcd(x: u128) -> Option<u128> {
+    format!("{:x}", x).parse().ok()
+}
+
+pub fn gray(x: u128) -> u128 {
+    x ^ (x >> 1)
+}
+
+pub fn from_gray(x: u128) -> u128 {
+    [1, 2, 4, 8, 16, 32, 64]
+        .iter()
+        .fold(x, |x, shift| x ^ (x >> shift))
+}
+
+fn show_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// The value as a protobuf varint, a ZigZag `sint` varint and signed LEB128
+pub fn describe(x: i128) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some(bytes) = varint(x) {
+        rows.push(("Var", show_bytes(&bytes)));
+    }
+    rows.push(("SVar", show_bytes(&uleb128(zigzag(x)))));
+    rows.push(("SLEB", show_bytes(&sleb128(x))));
+    rows
+}
+
+#[test]
+fn test_leb128() {
+    assert_eq!(uleb128(0), vec![0]);
+    assert_eq!(uleb128(300), vec![0xac, 0x02]);
+    assert_eq!(uleb128(624_485), vec![0xe5, 0x8e, 0x26]);
+    assert_eq!(sleb128(-123_456), vec![0xc0, 0xbb, 0x78]);
+    assert_eq!(sleb128(63), vec![0x3f]);
+    assert_eq!(sleb128(64), vec![0xc0, 0x00]);
+    assert_eq!(sleb128(-64), vec![0x40]);
+    assert_eq!(from_uleb128(&[0xe5, 0x8e, 0x26]), Some(624_485));
+    assert_eq!(from_sleb128(&[0xc0, 0xbb, 0x78]), Some(-123_456));
+    assert_eq!(from_sleb128(&[0x40]), Some(-64));
+    assert_eq!(from_uleb128(&uleb128(u128::MAX)), Some(u128::MAX));
+    assert_eq!(from_sleb128(&sleb128(i128::MIN)), Some(i128::MIN));
+    assert_eq!(from_uleb128(&[0x80]), None);
+    assert_eq!(from_uleb128(&[0x01, 0x01]), None);
+    assert_eq!(from_uleb128(&[]), None);
+    let mut too_big = uleb128(u128::MAX);
+    *too_big.last_mut().unwrap() = 0x04;
+    assert_eq!(from_uleb128(&too_big), None);
+    assert_eq!(varint(-1).unwrap().len(), 10);
+    assert_eq!(varint(i128::MIN), None);
+    assert_eq!(from_varint(&varint(-1).unwrap()), Some(-1));
+    assert_eq!(
+        from_varint(&varint(i128::from(i64::MIN)).unwrap()),
+        Some(i128::from(i64::MIN))
+    );
+    assert_eq!(from_varint(&[0xac, 0x02]), Some(300));
+    assert_eq!(from_varint(&uleb128(1 << 64)), None);
+}
+
+#[test]
+fn test_zigzag_bcd_gray() {
+    assert_eq!(zigzag(0), 0);
+    assert_eq!(zigzag(-1), 1);
+    assert_eq!(zigzag(1), 2);
+    assert_eq!(zigzag(-2), 3);
+    assert_eq!(zigzag(2_147_483_647), 4_294_967_294);
+    assert_eq!(from_zigzag(3), -2);
+    assert_eq!(from_zigzag(zigzag(i128::MIN)), i128::MIN);
+    assert_eq!(bcd(1234), Some(0x1234));
+    assert_eq!(bcd(0), Some(0));
+    assert_eq!(from_bcd(0x1234), Some(1234));
+    assert_eq!(from_bcd(0x12a4), None);
+    assert_eq!(gray(5), 7);
+    assert_eq!(gray(8), 12);
+    assert_eq!(from_gray(7), 5);
+    assert_eq!(from_gray(gray(u128::MAX - 5)), u128::MAX - 5);
+    assert_eq!(
+        describe(-1),
+        vec![
+            ("Var", String::from("ff ff ff ff ff ff ff ff ff 01")),
+            ("SVar", String::from("01")),
+            ("SLEB", String::from("7f")),
+        ]
+    );
+}